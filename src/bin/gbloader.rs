@@ -0,0 +1,448 @@
+//! Command line tool to inspect and repair Game Boy ROM headers, similar to `rgbfix`.
+
+use std::env;
+use std::fs;
+use std::process;
+
+use gbloader::checksum::{self, ChecksumMismatch};
+use gbloader::licensee;
+use gbloader::logo::Model;
+use gbloader::size::verify_rom_size;
+use gbloader::{CgbSupport, HeaderEditor, HeaderView, LoadError, DMG};
+use serde::{Serialize, Serializer};
+
+const USAGE: &str = "\
+Usage: gbloader <command> [options] <rom>
+
+Commands:
+    info [--json] <rom>                 Print the decoded header
+    verify [--json] <rom>               Check checksums, logo and sizes, exit with 1 on failure
+    fix [-o <out>] <rom>                Rewrite the logo and both checksums
+    pad [--fill <byte>] [-o <out>] <rom>
+                                        Pad to the next valid ROM size and fix the checksums
+    set-title <title> [-o <out>] <rom>  Set the title and fix the checksums, a CGB title
+                                        replaces any manufacturer code
+
+Files are modified in place unless -o is given.";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Info {
+        json: bool,
+        rom: String,
+    },
+    Verify {
+        json: bool,
+        rom: String,
+    },
+    Fix {
+        output: Option<String>,
+        rom: String,
+    },
+    Pad {
+        fill: u8,
+        output: Option<String>,
+        rom: String,
+    },
+    SetTitle {
+        title: String,
+        output: Option<String>,
+        rom: String,
+    },
+}
+
+fn parse_byte(value: &str) -> Result<u8, String> {
+    let parsed = if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        u8::from_str_radix(hex, 16)
+    } else {
+        value.parse()
+    };
+    parsed.map_err(|_| format!("invalid byte value '{}'", value))
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = args.split_first().ok_or("missing command")?;
+
+    let mut json = false;
+    let mut fill = None;
+    let mut output = None;
+    let mut positional = vec![];
+
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-o" | "--output" => output = Some(rest.next().ok_or("missing value for -o")?.clone()),
+            "--fill" => fill = Some(parse_byte(rest.next().ok_or("missing value for --fill")?)?),
+            option if option.starts_with('-') && option.len() > 1 => {
+                return Err(format!("unknown option '{}'", option))
+            }
+            value => positional.push(value.to_string()),
+        }
+    }
+
+    // Options each command accepts
+    let (accepts_json, accepts_fill, accepts_output) = match command.as_str() {
+        "info" | "verify" => (true, false, false),
+        "fix" | "set-title" => (false, false, true),
+        "pad" => (false, true, true),
+        command => return Err(format!("unknown command '{}'", command)),
+    };
+    for (given, accepted, option) in [
+        (json, accepts_json, "--json"),
+        (fill.is_some(), accepts_fill, "--fill"),
+        (output.is_some(), accepts_output, "-o"),
+    ] {
+        if given && !accepted {
+            return Err(format!("'{}' does not accept {}", command, option));
+        }
+    }
+
+    let expected = if command == "set-title" { 2 } else { 1 };
+    if positional.len() != expected {
+        return Err(format!(
+            "'{}' expects {} argument(s), got {}",
+            command,
+            expected,
+            positional.len()
+        ));
+    }
+    let rom = positional.pop().unwrap();
+
+    match command.as_str() {
+        "info" => Ok(Command::Info { json, rom }),
+        "verify" => Ok(Command::Verify { json, rom }),
+        "fix" => Ok(Command::Fix { output, rom }),
+        "pad" => Ok(Command::Pad {
+            fill: fill.unwrap_or(0xFF),
+            output,
+            rom,
+        }),
+        "set-title" => Ok(Command::SetTitle {
+            title: positional.pop().unwrap(),
+            output,
+            rom,
+        }),
+        _ => unreachable!(),
+    }
+}
+
+fn cgb_support_name(cgb_support: CgbSupport) -> &'static str {
+    match cgb_support {
+        CgbSupport::DmgOnly => "DMG only",
+        CgbSupport::CgbEnhanced => "CGB enhanced",
+        CgbSupport::CgbOnly => "CGB only",
+        CgbSupport::Pgb => "PGB mode",
+    }
+}
+
+// Check results as a JSON object, in the order the checks ran
+struct ChecksJson<'a>(&'a [(&'static str, Option<String>)]);
+
+impl Serialize for ChecksJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(name, failure)| (name, failure)))
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("header JSON is always serializable")
+}
+
+fn print_info(header: &DMG) {
+    println!("Title:            {}", header.get_title());
+    if let Some(code) = header.manufacturer_code() {
+        println!("Manufacturer:     {}", code);
+    }
+    println!(
+        "CGB:              {} (0x{:02X})",
+        cgb_support_name(header.cgb_support()),
+        header.get_cgb_flag()
+    );
+    println!("SGB:              {}", header.sgb_status());
+    println!("Cartridge type:   {}", header.cartridge_type());
+    println!("ROM size:         {}", header.rom_size());
+    println!("RAM size:         {}", header.ram_size());
+    println!("Destination:      {}", header.destination());
+    println!("Region:           {}", header.region().region);
+    println!(
+        "Publisher:        {}",
+        header.publisher().unwrap_or("Unknown")
+    );
+    println!("Version:          {}", header.get_mask_romversion_number());
+    println!(
+        "Header checksum:  0x{:02X} (computed 0x{:02X})",
+        header.get_header_checksum(),
+        header.computed_header_checksum()
+    );
+    println!(
+        "Global checksum:  0x{:04X} (computed 0x{:04X})",
+        header.get_global_checksum(),
+        header.computed_global_checksum()
+    );
+}
+
+// Invalid byte in the new licensee code, which `DMG` refuses to load
+fn licensee_code_error(header: &HeaderView) -> Option<LoadError> {
+    if header.get_license_code() != licensee::USE_NEW_LICENSEE_CODE {
+        return None;
+    }
+    (0x144..0x146)
+        .map(|offset| (offset, header.as_bytes()[offset - 0x100]))
+        .find(|&(_, byte)| !byte.is_ascii())
+        .map(|(offset, byte)| LoadError::InvalidLicenseeCode { offset, byte })
+}
+
+// Runs every check, returning the name of each one and its failure, if any. Only a ROM too
+// short to hold a header is an error, so damaged headers can still be checked.
+fn checks(rom_data: &[u8]) -> Result<Vec<(&'static str, Option<String>)>, LoadError> {
+    let header = HeaderView::new(rom_data)?;
+    let global_checksum = ChecksumMismatch {
+        stored: header.get_global_checksum(),
+        computed: checksum::global_checksum(rom_data),
+    };
+
+    let logo = if header.is_logo_valid(Model::Dmg) {
+        None
+    } else {
+        Some(format!(
+            "{} byte(s) differ from the Nintendo logo",
+            header.logo_diff().count()
+        ))
+    };
+
+    Ok(vec![
+        ("logo", logo),
+        (
+            "header_checksum",
+            header.verify_header_checksum().err().map(|e| e.to_string()),
+        ),
+        (
+            "global_checksum",
+            (global_checksum.stored != global_checksum.computed)
+                .then(|| global_checksum.to_string()),
+        ),
+        (
+            "rom_size",
+            verify_rom_size(header.rom_size(), rom_data.len())
+                .err()
+                .map(|e| e.to_string()),
+        ),
+        (
+            "ram_size",
+            header.verify_ram_size().err().map(|e| e.to_string()),
+        ),
+        (
+            "licensee_code",
+            licensee_code_error(&header).map(|e| e.to_string()),
+        ),
+    ])
+}
+
+fn verify(path: &str, json: bool) -> Result<bool, String> {
+    let rom_data = read(path)?;
+    let checks = checks(&rom_data).map_err(|e| format!("{}: {}", path, e))?;
+    let ok = checks.iter().all(|(_, failure)| failure.is_none());
+
+    if json {
+        println!("{}", to_json(&ChecksJson(&checks)));
+    } else {
+        for (name, failure) in &checks {
+            match failure {
+                None => println!("{:<16} OK", name),
+                Some(failure) => println!("{:<16} FAILED: {}", name, failure),
+            }
+        }
+    }
+
+    Ok(ok)
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn load(path: &str) -> Result<DMG, String> {
+    DMG::from_path(path).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn edit(
+    path: &str,
+    output: Option<&str>,
+    edit: impl FnOnce(&mut HeaderEditor) -> Result<(), String>,
+) -> Result<(), String> {
+    // Only the length is checked, so headers DMG can not parse can still be repaired
+    let mut editor = HeaderEditor::new(read(path)?).map_err(|e| format!("{}: {}", path, e))?;
+    edit(&mut editor)?;
+    editor.fix_checksums();
+
+    let output = output.unwrap_or(path);
+    fs::write(output, editor.rom_data()).map_err(|e| format!("{}: {}", output, e))
+}
+
+fn run(command: Command) -> Result<bool, String> {
+    match command {
+        Command::Info { json, rom } => {
+            let header = load(&rom)?;
+            if json {
+                // Same layout as `Serialize for DMG`, so scripts can use either
+                println!("{}", to_json(&header));
+            } else {
+                print_info(&header);
+            }
+            Ok(true)
+        }
+        Command::Verify { json, rom } => verify(&rom, json),
+        Command::Fix { output, rom } => edit(&rom, output.as_deref(), |editor| {
+            editor.fix_logo();
+            Ok(())
+        })
+        .map(|_| true),
+        Command::Pad { fill, output, rom } => edit(&rom, output.as_deref(), |editor| {
+            editor.pad(fill).map(|_| ()).map_err(|e| e.to_string())
+        })
+        .map(|_| true),
+        Command::SetTitle { title, output, rom } => edit(&rom, output.as_deref(), |editor| {
+            editor
+                .set_title(&title)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .map(|_| true),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() || args[0] == "-h" || args[0] == "--help" {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("gbloader: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match run(command) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("gbloader: {}", e);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_info() {
+        assert_eq!(
+            parse_args(&args(&["info", "--json", "game.gb"])),
+            Ok(Command::Info {
+                json: true,
+                rom: "game.gb".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_pad() {
+        assert_eq!(
+            parse_args(&args(&["pad", "--fill", "0x00", "-o", "out.gb", "game.gb"])),
+            Ok(Command::Pad {
+                fill: 0x00,
+                output: Some("out.gb".to_string()),
+                rom: "game.gb".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_set_title() {
+        assert_eq!(
+            parse_args(&args(&["set-title", "TETRIS", "game.gb"])),
+            Ok(Command::SetTitle {
+                title: "TETRIS".to_string(),
+                output: None,
+                rom: "game.gb".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_args(&args(&["info"])).is_err());
+        assert!(parse_args(&args(&["info", "--bogus", "game.gb"])).is_err());
+        assert!(parse_args(&args(&["frobnicate", "game.gb"])).is_err());
+        assert!(parse_args(&args(&["pad", "--fill", "256", "game.gb"])).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_unused_options() {
+        assert!(parse_args(&args(&["fix", "--json", "game.gb"])).is_err());
+        assert!(parse_args(&args(&["info", "--fill", "0x00", "game.gb"])).is_err());
+        assert!(parse_args(&args(&["verify", "-o", "out.gb", "game.gb"])).is_err());
+        assert!(parse_args(&args(&["set-title", "--fill", "0", "A", "game.gb"])).is_err());
+    }
+
+    #[test]
+    fn info_json() {
+        let header = load("test_roms/header_only_test.gb").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&to_json(&header)).unwrap();
+        assert_eq!(json, serde_json::to_value(&header).unwrap());
+        assert_eq!(json["cartridge_type"]["code"], 0x01);
+    }
+
+    #[test]
+    fn verify_json_keeps_order() {
+        let checks = [("logo", None), ("rom_size", Some("too short".to_string()))];
+        assert_eq!(
+            serde_json::to_string(&ChecksJson(&checks)).unwrap(),
+            r#"{"logo":null,"rom_size":"too short"}"#
+        );
+    }
+
+    #[test]
+    fn verify_fixture() {
+        let rom_data = read("test_roms/header_only_test.gb").unwrap();
+        let checks = checks(&rom_data).unwrap();
+        assert_eq!(checks[0], ("logo", None));
+        assert_eq!(checks[1], ("header_checksum", None));
+        assert!(checks[2].1.is_some());
+        assert_eq!(checks[5], ("licensee_code", None));
+    }
+
+    #[test]
+    fn verify_invalid_licensee_code() {
+        let mut rom_data = read("test_roms/header_only_test.gb").unwrap();
+        rom_data[0x145] = 0x80;
+        let checks = checks(&rom_data).unwrap();
+        assert_eq!(
+            checks[5],
+            (
+                "licensee_code",
+                Some("invalid byte 0x80 in new licensee code at offset 0x0145".to_string())
+            )
+        );
+        assert!(checks[1].1.is_some());
+    }
+
+    #[test]
+    fn verify_too_short() {
+        assert!(checks(&[0; 0x100]).is_err());
+    }
+}
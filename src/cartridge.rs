@@ -0,0 +1,348 @@
+//! Cartridge hardware described by the header, such as the memory bank controller.
+
+use core::fmt;
+
+/// Memory bank controller, or other chip, mapping the cartridge into the address space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Mapper {
+    None,
+    Mbc1,
+    Mbc2,
+    Mmm01,
+    Mbc3,
+    Mbc5,
+    Mbc6,
+    Mbc7,
+    PocketCamera,
+    Tama5,
+    HuC3,
+    HuC1,
+    Unknown,
+}
+
+/// Cartridge type read from 0x0147.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CartridgeType {
+    RomOnly,                    // 0x00
+    Mbc1,                       // 0x01
+    Mbc1Ram,                    // 0x02
+    Mbc1RamBattery,             // 0x03
+    Mbc2,                       // 0x05
+    Mbc2Battery,                // 0x06
+    RomRam,                     // 0x08
+    RomRamBattery,              // 0x09
+    Mmm01,                      // 0x0B
+    Mmm01Ram,                   // 0x0C
+    Mmm01RamBattery,            // 0x0D
+    Mbc3TimerBattery,           // 0x0F
+    Mbc3TimerRamBattery,        // 0x10
+    Mbc3,                       // 0x11
+    Mbc3Ram,                    // 0x12
+    Mbc3RamBattery,             // 0x13
+    Mbc5,                       // 0x19
+    Mbc5Ram,                    // 0x1A
+    Mbc5RamBattery,             // 0x1B
+    Mbc5Rumble,                 // 0x1C
+    Mbc5RumbleRam,              // 0x1D
+    Mbc5RumbleRamBattery,       // 0x1E
+    Mbc6,                       // 0x20
+    Mbc7SensorRumbleRamBattery, // 0x22
+    PocketCamera,               // 0xFC
+    Tama5,                      // 0xFD
+    HuC3,                       // 0xFE
+    HuC1RamBattery,             // 0xFF
+    Unknown(u8),
+}
+
+impl CartridgeType {
+    pub fn from_code(code: u8) -> CartridgeType {
+        match code {
+            0x00 => CartridgeType::RomOnly,
+            0x01 => CartridgeType::Mbc1,
+            0x02 => CartridgeType::Mbc1Ram,
+            0x03 => CartridgeType::Mbc1RamBattery,
+            0x05 => CartridgeType::Mbc2,
+            0x06 => CartridgeType::Mbc2Battery,
+            0x08 => CartridgeType::RomRam,
+            0x09 => CartridgeType::RomRamBattery,
+            0x0B => CartridgeType::Mmm01,
+            0x0C => CartridgeType::Mmm01Ram,
+            0x0D => CartridgeType::Mmm01RamBattery,
+            0x0F => CartridgeType::Mbc3TimerBattery,
+            0x10 => CartridgeType::Mbc3TimerRamBattery,
+            0x11 => CartridgeType::Mbc3,
+            0x12 => CartridgeType::Mbc3Ram,
+            0x13 => CartridgeType::Mbc3RamBattery,
+            0x19 => CartridgeType::Mbc5,
+            0x1A => CartridgeType::Mbc5Ram,
+            0x1B => CartridgeType::Mbc5RamBattery,
+            0x1C => CartridgeType::Mbc5Rumble,
+            0x1D => CartridgeType::Mbc5RumbleRam,
+            0x1E => CartridgeType::Mbc5RumbleRamBattery,
+            0x20 => CartridgeType::Mbc6,
+            0x22 => CartridgeType::Mbc7SensorRumbleRamBattery,
+            0xFC => CartridgeType::PocketCamera,
+            0xFD => CartridgeType::Tama5,
+            0xFE => CartridgeType::HuC3,
+            0xFF => CartridgeType::HuC1RamBattery,
+            code => CartridgeType::Unknown(code),
+        }
+    }
+
+    /// The raw value stored at 0x0147.
+    pub fn code(self) -> u8 {
+        match self {
+            CartridgeType::RomOnly => 0x00,
+            CartridgeType::Mbc1 => 0x01,
+            CartridgeType::Mbc1Ram => 0x02,
+            CartridgeType::Mbc1RamBattery => 0x03,
+            CartridgeType::Mbc2 => 0x05,
+            CartridgeType::Mbc2Battery => 0x06,
+            CartridgeType::RomRam => 0x08,
+            CartridgeType::RomRamBattery => 0x09,
+            CartridgeType::Mmm01 => 0x0B,
+            CartridgeType::Mmm01Ram => 0x0C,
+            CartridgeType::Mmm01RamBattery => 0x0D,
+            CartridgeType::Mbc3TimerBattery => 0x0F,
+            CartridgeType::Mbc3TimerRamBattery => 0x10,
+            CartridgeType::Mbc3 => 0x11,
+            CartridgeType::Mbc3Ram => 0x12,
+            CartridgeType::Mbc3RamBattery => 0x13,
+            CartridgeType::Mbc5 => 0x19,
+            CartridgeType::Mbc5Ram => 0x1A,
+            CartridgeType::Mbc5RamBattery => 0x1B,
+            CartridgeType::Mbc5Rumble => 0x1C,
+            CartridgeType::Mbc5RumbleRam => 0x1D,
+            CartridgeType::Mbc5RumbleRamBattery => 0x1E,
+            CartridgeType::Mbc6 => 0x20,
+            CartridgeType::Mbc7SensorRumbleRamBattery => 0x22,
+            CartridgeType::PocketCamera => 0xFC,
+            CartridgeType::Tama5 => 0xFD,
+            CartridgeType::HuC3 => 0xFE,
+            CartridgeType::HuC1RamBattery => 0xFF,
+            CartridgeType::Unknown(code) => code,
+        }
+    }
+
+    /// Name as listed in the Pan Docs, e.g. "MBC1+RAM+BATTERY".
+    pub fn name(self) -> &'static str {
+        match self {
+            CartridgeType::RomOnly => "ROM ONLY",
+            CartridgeType::Mbc1 => "MBC1",
+            CartridgeType::Mbc1Ram => "MBC1+RAM",
+            CartridgeType::Mbc1RamBattery => "MBC1+RAM+BATTERY",
+            CartridgeType::Mbc2 => "MBC2",
+            CartridgeType::Mbc2Battery => "MBC2+BATTERY",
+            CartridgeType::RomRam => "ROM+RAM",
+            CartridgeType::RomRamBattery => "ROM+RAM+BATTERY",
+            CartridgeType::Mmm01 => "MMM01",
+            CartridgeType::Mmm01Ram => "MMM01+RAM",
+            CartridgeType::Mmm01RamBattery => "MMM01+RAM+BATTERY",
+            CartridgeType::Mbc3TimerBattery => "MBC3+TIMER+BATTERY",
+            CartridgeType::Mbc3TimerRamBattery => "MBC3+TIMER+RAM+BATTERY",
+            CartridgeType::Mbc3 => "MBC3",
+            CartridgeType::Mbc3Ram => "MBC3+RAM",
+            CartridgeType::Mbc3RamBattery => "MBC3+RAM+BATTERY",
+            CartridgeType::Mbc5 => "MBC5",
+            CartridgeType::Mbc5Ram => "MBC5+RAM",
+            CartridgeType::Mbc5RamBattery => "MBC5+RAM+BATTERY",
+            CartridgeType::Mbc5Rumble => "MBC5+RUMBLE",
+            CartridgeType::Mbc5RumbleRam => "MBC5+RUMBLE+RAM",
+            CartridgeType::Mbc5RumbleRamBattery => "MBC5+RUMBLE+RAM+BATTERY",
+            CartridgeType::Mbc6 => "MBC6",
+            CartridgeType::Mbc7SensorRumbleRamBattery => "MBC7+SENSOR+RUMBLE+RAM+BATTERY",
+            CartridgeType::PocketCamera => "POCKET CAMERA",
+            CartridgeType::Tama5 => "BANDAI TAMA5",
+            CartridgeType::HuC3 => "HuC3",
+            CartridgeType::HuC1RamBattery => "HuC1+RAM+BATTERY",
+            CartridgeType::Unknown(_) => "UNKNOWN",
+        }
+    }
+
+    pub fn mapper(self) -> Mapper {
+        match self {
+            CartridgeType::RomOnly | CartridgeType::RomRam | CartridgeType::RomRamBattery => {
+                Mapper::None
+            }
+            CartridgeType::Mbc1 | CartridgeType::Mbc1Ram | CartridgeType::Mbc1RamBattery => {
+                Mapper::Mbc1
+            }
+            CartridgeType::Mbc2 | CartridgeType::Mbc2Battery => Mapper::Mbc2,
+            CartridgeType::Mmm01 | CartridgeType::Mmm01Ram | CartridgeType::Mmm01RamBattery => {
+                Mapper::Mmm01
+            }
+            CartridgeType::Mbc3TimerBattery
+            | CartridgeType::Mbc3TimerRamBattery
+            | CartridgeType::Mbc3
+            | CartridgeType::Mbc3Ram
+            | CartridgeType::Mbc3RamBattery => Mapper::Mbc3,
+            CartridgeType::Mbc5
+            | CartridgeType::Mbc5Ram
+            | CartridgeType::Mbc5RamBattery
+            | CartridgeType::Mbc5Rumble
+            | CartridgeType::Mbc5RumbleRam
+            | CartridgeType::Mbc5RumbleRamBattery => Mapper::Mbc5,
+            CartridgeType::Mbc6 => Mapper::Mbc6,
+            CartridgeType::Mbc7SensorRumbleRamBattery => Mapper::Mbc7,
+            CartridgeType::PocketCamera => Mapper::PocketCamera,
+            CartridgeType::Tama5 => Mapper::Tama5,
+            CartridgeType::HuC3 => Mapper::HuC3,
+            CartridgeType::HuC1RamBattery => Mapper::HuC1,
+            CartridgeType::Unknown(_) => Mapper::Unknown,
+        }
+    }
+
+    /// Whether the cartridge has RAM, either external or built into the mapper.
+    pub fn has_ram(self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc1Ram
+                | CartridgeType::Mbc1RamBattery
+                | CartridgeType::Mbc2
+                | CartridgeType::Mbc2Battery
+                | CartridgeType::RomRam
+                | CartridgeType::RomRamBattery
+                | CartridgeType::Mmm01Ram
+                | CartridgeType::Mmm01RamBattery
+                | CartridgeType::Mbc3TimerRamBattery
+                | CartridgeType::Mbc3Ram
+                | CartridgeType::Mbc3RamBattery
+                | CartridgeType::Mbc5Ram
+                | CartridgeType::Mbc5RamBattery
+                | CartridgeType::Mbc5RumbleRam
+                | CartridgeType::Mbc5RumbleRamBattery
+                | CartridgeType::Mbc6
+                | CartridgeType::Mbc7SensorRumbleRamBattery
+                | CartridgeType::PocketCamera
+                | CartridgeType::Tama5
+                | CartridgeType::HuC3
+                | CartridgeType::HuC1RamBattery
+        )
+    }
+
+    /// Whether RAM (or the clock) keeps its contents while the console is off.
+    pub fn has_battery(self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc1RamBattery
+                | CartridgeType::Mbc2Battery
+                | CartridgeType::RomRamBattery
+                | CartridgeType::Mmm01RamBattery
+                | CartridgeType::Mbc3TimerBattery
+                | CartridgeType::Mbc3TimerRamBattery
+                | CartridgeType::Mbc3RamBattery
+                | CartridgeType::Mbc5RamBattery
+                | CartridgeType::Mbc5RumbleRamBattery
+                | CartridgeType::Mbc7SensorRumbleRamBattery
+                | CartridgeType::PocketCamera
+                | CartridgeType::Tama5
+                | CartridgeType::HuC3
+                | CartridgeType::HuC1RamBattery
+        )
+    }
+
+    /// Whether the cartridge has a real-time clock.
+    pub fn has_rtc(self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc3TimerBattery
+                | CartridgeType::Mbc3TimerRamBattery
+                | CartridgeType::Tama5
+                | CartridgeType::HuC3
+        )
+    }
+
+    pub fn has_rumble(self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc5Rumble
+                | CartridgeType::Mbc5RumbleRam
+                | CartridgeType::Mbc5RumbleRamBattery
+                | CartridgeType::Mbc7SensorRumbleRamBattery
+        )
+    }
+
+    /// Whether the cartridge has the MBC7 accelerometer.
+    pub fn has_sensor(self) -> bool {
+        self == CartridgeType::Mbc7SensorRumbleRamBattery
+    }
+}
+
+impl From<u8> for CartridgeType {
+    fn from(code: u8) -> CartridgeType {
+        CartridgeType::from_code(code)
+    }
+}
+
+impl From<CartridgeType> for u8 {
+    fn from(cartridge_type: CartridgeType) -> u8 {
+        cartridge_type.code()
+    }
+}
+
+impl fmt::Display for CartridgeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CartridgeType::Unknown(code) => write!(f, "UNKNOWN (0x{:02X})", code),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_round_trip() {
+        for code in 0..=0xFF {
+            assert_eq!(CartridgeType::from_code(code).code(), code);
+        }
+    }
+
+    #[test]
+    fn unknown_codes() {
+        assert_eq!(CartridgeType::from_code(0x04), CartridgeType::Unknown(0x04));
+        assert_eq!(CartridgeType::from_code(0x04).mapper(), Mapper::Unknown);
+        assert_eq!(CartridgeType::from_code(0x04).to_string(), "UNKNOWN (0x04)");
+    }
+
+    #[test]
+    fn mbc3_timer_ram_battery() {
+        let cartridge_type = CartridgeType::from_code(0x10);
+        assert_eq!(cartridge_type.mapper(), Mapper::Mbc3);
+        assert_eq!(cartridge_type.to_string(), "MBC3+TIMER+RAM+BATTERY");
+        assert!(cartridge_type.has_ram());
+        assert!(cartridge_type.has_battery());
+        assert!(cartridge_type.has_rtc());
+        assert!(!cartridge_type.has_rumble());
+    }
+
+    #[test]
+    fn mbc5_rumble() {
+        let cartridge_type = CartridgeType::from_code(0x1C);
+        assert_eq!(cartridge_type.mapper(), Mapper::Mbc5);
+        assert!(cartridge_type.has_rumble());
+        assert!(!cartridge_type.has_ram());
+        assert!(!cartridge_type.has_battery());
+    }
+
+    #[test]
+    fn mbc7_sensor() {
+        let cartridge_type = CartridgeType::from_code(0x22);
+        assert_eq!(cartridge_type.mapper(), Mapper::Mbc7);
+        assert!(cartridge_type.has_sensor());
+        assert!(cartridge_type.has_rumble());
+        assert!(cartridge_type.has_battery());
+    }
+
+    #[test]
+    fn rom_only() {
+        let cartridge_type = CartridgeType::from_code(0x00);
+        assert_eq!(cartridge_type.mapper(), Mapper::None);
+        assert!(!cartridge_type.has_ram());
+        assert!(!cartridge_type.has_battery());
+    }
+}
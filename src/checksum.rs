@@ -0,0 +1,166 @@
+//! Header and global checksums as computed by the boot ROM and by Nintendo's tooling.
+
+use core::{fmt, mem};
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::error::LoadError;
+use crate::header::HEADER_END;
+
+pub const HEADER_CHECKSUM_START: usize = 0x134;
+pub const HEADER_CHECKSUM_END: usize = 0x14D;
+pub const HEADER_CHECKSUM_ADDRESS: usize = 0x14D;
+pub const GLOBAL_CHECKSUM_ADDRESS: usize = 0x14E;
+
+/// Stored and computed values of a checksum that did not match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ChecksumMismatch<T> {
+    pub stored: T,
+    pub computed: T,
+}
+
+impl<T: fmt::UpperHex> fmt::Display for ChecksumMismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Two digits per byte, plus the 0x prefix
+        let width = 2 + 2 * mem::size_of::<T>();
+        write!(
+            f,
+            "checksum mismatch: stored {:#0width$X}, computed {:#0width$X}",
+            self.stored,
+            self.computed,
+            width = width
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug + fmt::UpperHex> Error for ChecksumMismatch<T> {}
+
+fn check_header_len(rom_data: &[u8]) -> Result<(), LoadError> {
+    if rom_data.len() < HEADER_END {
+        return Err(LoadError::TooShort {
+            len: rom_data.len(),
+            required: HEADER_END,
+        });
+    }
+    Ok(())
+}
+
+/// Computes the header checksum over 0x0134 - 0x014C the same way the boot ROM does.
+///
+/// `rom_data` must contain at least the bytes up to 0x014C.
+pub fn header_checksum(rom_data: &[u8]) -> u8 {
+    sum_header(&rom_data[HEADER_CHECKSUM_START..HEADER_CHECKSUM_END])
+}
+
+// Header checksum of the bytes 0x0134 - 0x014C on their own
+pub(crate) fn sum_header(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |x, &byte| x.wrapping_sub(byte).wrapping_sub(1))
+}
+
+/// Writes the correct header checksum to 0x014D and returns it.
+pub fn fix_header_checksum(rom_data: &mut [u8]) -> Result<u8, LoadError> {
+    check_header_len(rom_data)?;
+    let checksum = header_checksum(rom_data);
+    rom_data[HEADER_CHECKSUM_ADDRESS] = checksum;
+    Ok(checksum)
+}
+
+/// Computes the 16-bit sum of every byte of the ROM image, except the two global checksum bytes
+/// at 0x014E - 0x014F.
+pub fn global_checksum(rom_data: &[u8]) -> u16 {
+    rom_data
+        .iter()
+        .enumerate()
+        .filter(|&(address, _)| {
+            address != GLOBAL_CHECKSUM_ADDRESS && address != GLOBAL_CHECKSUM_ADDRESS + 1
+        })
+        .fold(0u16, |sum, (_, &byte)| sum.wrapping_add(byte as u16))
+}
+
+/// Writes the correct global checksum to 0x014E - 0x014F (big endian) and returns it.
+///
+/// The header checksum is part of the sum, so it should be fixed first.
+pub fn fix_global_checksum(rom_data: &mut [u8]) -> Result<u16, LoadError> {
+    check_header_len(rom_data)?;
+    let checksum = global_checksum(rom_data);
+    rom_data[GLOBAL_CHECKSUM_ADDRESS] = (checksum >> 8) as u8;
+    rom_data[GLOBAL_CHECKSUM_ADDRESS + 1] = checksum as u8;
+    Ok(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_checksum_blank() {
+        // 0x19 bytes of zero each subtract one
+        assert_eq!(header_checksum(&[0; 0x150]), 0xE7);
+    }
+
+    #[test]
+    fn fix_header_checksum_writes_byte() {
+        let mut rom = vec![0; 0x150];
+        rom[0x134..0x13A].copy_from_slice(b"TETRIS");
+
+        let checksum = fix_header_checksum(&mut rom).unwrap();
+        assert_eq!(rom[0x14D], checksum);
+        assert_eq!(header_checksum(&rom), checksum);
+    }
+
+    #[test]
+    fn fix_header_checksum_too_short() {
+        let mut rom = vec![0; 0x14D];
+        assert!(fix_header_checksum(&mut rom).is_err());
+    }
+
+    #[test]
+    fn global_checksum_skips_checksum_bytes() {
+        let mut rom = vec![0; 0x150];
+        rom[0x100] = 0xFF;
+        rom[0x14E] = 0x12;
+        rom[0x14F] = 0x34;
+        assert_eq!(global_checksum(&rom), 0xFF);
+    }
+
+    #[test]
+    fn global_checksum_wraps() {
+        let rom = vec![0xFF; 0x8000];
+        assert_eq!(global_checksum(&rom), (0xFFu32 * 0x7FFE) as u16);
+    }
+
+    #[test]
+    fn fix_global_checksum_writes_big_endian() {
+        let mut rom = vec![0x11; 0x8000];
+
+        let checksum = fix_global_checksum(&mut rom).unwrap();
+        assert_eq!(rom[0x14E], (checksum >> 8) as u8);
+        assert_eq!(rom[0x14F], checksum as u8);
+        assert_eq!(global_checksum(&rom), checksum);
+    }
+
+    #[test]
+    fn mismatch_display() {
+        let mismatch = ChecksumMismatch {
+            stored: 0xFFu8,
+            computed: 0x1Bu8,
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            "checksum mismatch: stored 0xFF, computed 0x1B"
+        );
+
+        let mismatch = ChecksumMismatch {
+            stored: 0u16,
+            computed: 0x1669u16,
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            "checksum mismatch: stored 0x0000, computed 0x1669"
+        );
+    }
+}
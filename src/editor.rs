@@ -0,0 +1,403 @@
+//! Writing header fields back into a ROM image, like `rgbfix` does.
+
+use alloc::vec::Vec;
+
+use crate::cartridge::CartridgeType;
+use crate::checksum;
+use crate::error::{EditError, LoadError};
+use crate::header::{
+    CgbSupport, TitleLayout, CGB_FLAG_ADDRESS, DMG, HEADER_END, MANUFACTURER_CODE_START,
+    TITLE_START,
+};
+use crate::licensee::USE_NEW_LICENSEE_CODE;
+use crate::logo::{LOGO_END, LOGO_START, NINTENDO_LOGO};
+use crate::region::Destination;
+use crate::size::{RamSize, RomSize};
+use crate::view::HeaderView;
+
+/// Owns a ROM image and rewrites its header fields in place.
+///
+/// Setters only touch their own field, so the checksums are stale until
+/// [`fix_checksums`](HeaderEditor::fix_checksums) or [`finish`](HeaderEditor::finish) is called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderEditor {
+    rom_data: Vec<u8>,
+}
+
+impl HeaderEditor {
+    pub fn new(rom_data: Vec<u8>) -> Result<HeaderEditor, LoadError> {
+        if rom_data.len() < HEADER_END {
+            return Err(LoadError::TooShort {
+                len: rom_data.len(),
+                required: HEADER_END,
+            });
+        }
+
+        Ok(HeaderEditor { rom_data })
+    }
+
+    // View over the header, which `new` made sure is present
+    fn header_view(&self) -> HeaderView<'_> {
+        HeaderView::new(&self.rom_data).expect("HeaderEditor holds at least a full header")
+    }
+
+    /// Sets the title, padding the rest of the title space with NUL bytes. The layout
+    /// follows the CGB flag alone: 16 bytes when it is clear, otherwise 15 bytes, which also
+    /// clears any manufacturer code. Use
+    /// [`set_title_with_layout`](HeaderEditor::set_title_with_layout) to keep one.
+    pub fn set_title(&mut self, title: &str) -> Result<&mut HeaderEditor, EditError> {
+        let layout = match self.header_view().cgb_support() {
+            CgbSupport::DmgOnly => TitleLayout::Legacy,
+            _ => TitleLayout::Cgb,
+        };
+        self.set_title_with_layout(title, layout)
+    }
+
+    /// Sets the title in the space `layout` leaves for it, padding the rest with NUL bytes.
+    ///
+    /// `TitleLayout::Legacy` overwrites the CGB flag. `TitleLayout::Cgb` needs the CGB flag
+    /// and rejects a 15 character title whose last 4 characters would read back as a
+    /// manufacturer code. `TitleLayout::CgbWithManufacturerCode` needs the CGB flag and a
+    /// manufacturer code, and keeps both.
+    pub fn set_title_with_layout(
+        &mut self,
+        title: &str,
+        layout: TitleLayout,
+    ) -> Result<&mut HeaderEditor, EditError> {
+        let title_end = layout.title_end();
+        let max = title_end - TITLE_START;
+
+        if let Some(index) = title
+            .bytes()
+            .position(|byte| !(0x20..=0x7E).contains(&byte))
+        {
+            return Err(EditError::InvalidTitle { index });
+        }
+        if title.len() > max {
+            return Err(EditError::TitleTooLong {
+                len: title.len(),
+                max,
+            });
+        }
+
+        let header = self.header_view();
+        if layout != TitleLayout::Legacy && header.cgb_support() == CgbSupport::DmgOnly {
+            return Err(EditError::CgbFlagNotSet);
+        }
+        match layout {
+            TitleLayout::Legacy => {}
+            TitleLayout::Cgb => {
+                if title.len() == max
+                    && TitleLayout::from_fields(
+                        header.get_cgb_flag(),
+                        &title.as_bytes()[MANUFACTURER_CODE_START - TITLE_START..],
+                    ) == TitleLayout::CgbWithManufacturerCode
+                {
+                    return Err(EditError::AmbiguousTitle);
+                }
+            }
+            TitleLayout::CgbWithManufacturerCode => {
+                if header.manufacturer_code().is_none() {
+                    return Err(EditError::MissingManufacturerCode);
+                }
+            }
+        }
+
+        let field = &mut self.rom_data[TITLE_START..title_end];
+        field.iter_mut().for_each(|byte| *byte = 0);
+        field[..title.len()].copy_from_slice(title.as_bytes());
+        Ok(self)
+    }
+
+    /// Sets the 4 character manufacturer code at 0x013F - 0x0142, which cuts the title
+    /// down to 11 bytes. Only CGB headers have one, so the CGB flag must be set first.
+    pub fn set_manufacturer_code(&mut self, code: &str) -> Result<&mut HeaderEditor, EditError> {
+        if self.header_view().cgb_support() == CgbSupport::DmgOnly {
+            return Err(EditError::CgbFlagNotSet);
+        }
+        if code.len() != 4
+            || !code
+                .bytes()
+                .all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+        {
+            return Err(EditError::InvalidManufacturerCode);
+        }
+
+        self.rom_data[MANUFACTURER_CODE_START..CGB_FLAG_ADDRESS].copy_from_slice(code.as_bytes());
+        Ok(self)
+    }
+
+    /// Zeroes 0x013F - 0x0142, which hold the manufacturer code if there is one and the
+    /// end of the title otherwise.
+    pub fn clear_manufacturer_code(&mut self) -> &mut HeaderEditor {
+        self.rom_data[MANUFACTURER_CODE_START..CGB_FLAG_ADDRESS]
+            .iter_mut()
+            .for_each(|byte| *byte = 0);
+        self
+    }
+
+    /// Sets the CGB flag at 0x0143. Setting `CgbSupport::DmgOnly` writes 0x00, which ends
+    /// a 16 character title early.
+    pub fn set_cgb_support(&mut self, cgb_support: CgbSupport) -> &mut HeaderEditor {
+        self.rom_data[CGB_FLAG_ADDRESS] = cgb_support.flag();
+        self
+    }
+
+    pub fn set_sgb_flag(&mut self, sgb_flag: u8) -> &mut HeaderEditor {
+        self.rom_data[0x146] = sgb_flag;
+        self
+    }
+
+    pub fn set_cartridge_type(&mut self, cartridge_type: CartridgeType) -> &mut HeaderEditor {
+        self.rom_data[0x147] = cartridge_type.code();
+        self
+    }
+
+    pub fn set_rom_size(&mut self, rom_size: RomSize) -> &mut HeaderEditor {
+        self.rom_data[0x148] = rom_size.code();
+        self
+    }
+
+    pub fn set_ram_size(&mut self, ram_size: RamSize) -> &mut HeaderEditor {
+        self.rom_data[0x149] = ram_size.code();
+        self
+    }
+
+    pub fn set_destination(&mut self, destination: Destination) -> &mut HeaderEditor {
+        self.rom_data[0x14A] = destination.code();
+        self
+    }
+
+    /// Sets the old licensee code at 0x014B. Use
+    /// [`set_new_license_code`](HeaderEditor::set_new_license_code) for 0x33.
+    pub fn set_license_code(&mut self, license_code: u8) -> &mut HeaderEditor {
+        self.rom_data[0x14B] = license_code;
+        self
+    }
+
+    /// Sets the new licensee code at 0x0144 - 0x0145 and the old licensee code to 0x33.
+    pub fn set_new_license_code(&mut self, code: &str) -> Result<&mut HeaderEditor, EditError> {
+        if code.len() != 2 || !code.bytes().all(|byte| (0x20..=0x7E).contains(&byte)) {
+            return Err(EditError::InvalidLicenseeCode);
+        }
+
+        self.rom_data[0x144..0x146].copy_from_slice(code.as_bytes());
+        self.rom_data[0x14B] = USE_NEW_LICENSEE_CODE;
+        Ok(self)
+    }
+
+    pub fn set_mask_rom_version_number(&mut self, version: u8) -> &mut HeaderEditor {
+        self.rom_data[0x14C] = version;
+        self
+    }
+
+    /// Writes the Nintendo logo expected by the boot ROM.
+    pub fn fix_logo(&mut self) -> &mut HeaderEditor {
+        self.rom_data[LOGO_START..LOGO_END].copy_from_slice(&NINTENDO_LOGO);
+        self
+    }
+
+    /// Rewrites the header checksum, then the global checksum which includes it.
+    pub fn fix_checksums(&mut self) -> &mut HeaderEditor {
+        // The length was checked in new, so neither can fail
+        checksum::fix_header_checksum(&mut self.rom_data).unwrap();
+        checksum::fix_global_checksum(&mut self.rom_data).unwrap();
+        self
+    }
+
+    /// Pads the image with `fill` to the smallest official ROM size that holds it and
+    /// declares that size at 0x0148.
+    pub fn pad(&mut self, fill: u8) -> Result<&mut HeaderEditor, EditError> {
+        let rom_size = (0x00..=0x08)
+            .map(RomSize::from_code)
+            .find(|size| size.bytes().unwrap() >= self.rom_data.len())
+            .ok_or(EditError::RomTooLarge {
+                len: self.rom_data.len(),
+                max: RomSize::from_code(0x08).bytes().unwrap(),
+            })?;
+
+        self.rom_data.resize(rom_size.bytes().unwrap(), fill);
+        Ok(self.set_rom_size(rom_size))
+    }
+
+    pub fn rom_data(&self) -> &[u8] {
+        &self.rom_data
+    }
+
+    pub fn into_rom_data(self) -> Vec<u8> {
+        self.rom_data
+    }
+
+    /// Fixes both checksums and parses the result.
+    pub fn finish(mut self) -> Result<DMG, LoadError> {
+        self.fix_checksums();
+        DMG::new(self.rom_data)
+    }
+}
+
+impl From<DMG> for HeaderEditor {
+    fn from(dmg: DMG) -> HeaderEditor {
+        HeaderEditor {
+            rom_data: dmg.into_rom_data(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logo::Model;
+    use std::fs;
+
+    fn editor() -> HeaderEditor {
+        HeaderEditor::new(fs::read("test_roms/header_only_test.gb").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn new_too_short() {
+        assert!(HeaderEditor::new(vec![0; 0x100]).is_err());
+    }
+
+    #[test]
+    fn set_fields() {
+        let mut editor = editor();
+        editor
+            .set_cartridge_type(CartridgeType::Mbc3RamBattery)
+            .set_ram_size(RamSize::Kib32)
+            .set_sgb_flag(0x00)
+            .set_destination(Destination::Japan)
+            .set_mask_rom_version_number(2);
+        editor.set_new_license_code("08").unwrap();
+
+        let header = editor.finish().unwrap();
+        assert_eq!(header.cartridge_type(), CartridgeType::Mbc3RamBattery);
+        assert_eq!(header.get_ram_size(), 0x03);
+        assert_eq!(header.get_sgb_flag(), 0x00);
+        assert_eq!(header.destination(), Destination::Japan);
+        assert_eq!(header.get_mask_romversion_number(), 2);
+        assert_eq!(header.publisher(), Some("Capcom"));
+        assert!(header.verify_header_checksum().is_ok());
+        assert!(header.verify_global_checksum().is_ok());
+    }
+
+    #[test]
+    fn set_title_layouts() {
+        let mut editor = editor();
+        editor.set_title("TETRIS").unwrap();
+        assert_eq!(
+            &editor.rom_data()[0x134..0x144],
+            b"TETRIS\0\0\0\0\0\0\0\0\0\0"
+        );
+
+        editor.set_cgb_support(CgbSupport::CgbOnly);
+        assert_eq!(
+            editor.set_title("SIXTEEN CHARS!!!").err(),
+            Some(EditError::TitleTooLong { len: 16, max: 15 })
+        );
+
+        editor.set_manufacturer_code("AAXE").unwrap();
+        editor
+            .set_title_with_layout("POKEMON_SLV", TitleLayout::CgbWithManufacturerCode)
+            .unwrap();
+
+        let header = editor.finish().unwrap();
+        assert_eq!(header.get_title(), "POKEMON_SLV");
+        assert_eq!(header.manufacturer_code(), Some("AAXE"));
+        assert_eq!(header.cgb_support(), CgbSupport::CgbOnly);
+    }
+
+    #[test]
+    fn set_title_twice() {
+        let mut editor = editor();
+        editor.set_cgb_support(CgbSupport::CgbEnhanced);
+        // The old title ends in "T123", which must not shorten the new one
+        editor.set_title("MARIO LAND 2 DX").unwrap();
+        editor.set_title("MARIO LAND 2 DX").unwrap();
+
+        let header = editor.finish().unwrap();
+        assert_eq!(header.get_title(), "MARIO LAND 2 DX");
+        assert_eq!(header.manufacturer_code(), None);
+    }
+
+    #[test]
+    fn clear_manufacturer_code() {
+        let mut editor = editor();
+        editor.set_cgb_support(CgbSupport::CgbOnly);
+        editor.set_manufacturer_code("AAXE").unwrap();
+        editor
+            .set_title_with_layout("POKEMON_SLV", TitleLayout::CgbWithManufacturerCode)
+            .unwrap();
+        editor.clear_manufacturer_code();
+
+        let header = editor.finish().unwrap();
+        assert_eq!(header.get_title(), "POKEMON_SLV");
+        assert_eq!(header.manufacturer_code(), None);
+        assert_eq!(header.title_layout(), TitleLayout::Cgb);
+    }
+
+    #[test]
+    fn set_title_layout_errors() {
+        let mut editor = editor();
+        assert_eq!(
+            editor
+                .set_title_with_layout("TETRIS", TitleLayout::Cgb)
+                .err(),
+            Some(EditError::CgbFlagNotSet)
+        );
+
+        editor.set_cgb_support(CgbSupport::CgbEnhanced);
+        assert_eq!(
+            editor.set_title("SUPER MARIOLAND").err(),
+            Some(EditError::AmbiguousTitle)
+        );
+        editor.clear_manufacturer_code();
+        assert_eq!(
+            editor
+                .set_title_with_layout("TETRIS", TitleLayout::CgbWithManufacturerCode)
+                .err(),
+            Some(EditError::MissingManufacturerCode)
+        );
+    }
+
+    #[test]
+    fn set_title_invalid() {
+        let mut editor = editor();
+        assert_eq!(
+            editor.set_title("POKéMON").err(),
+            Some(EditError::InvalidTitle { index: 3 })
+        );
+        assert_eq!(
+            editor.set_manufacturer_code("AAXE").err(),
+            Some(EditError::CgbFlagNotSet)
+        );
+        assert_eq!(&editor.rom_data()[0x134..0x144], b"GBLOADERTEST1234");
+
+        editor.set_cgb_support(CgbSupport::CgbOnly);
+        assert_eq!(
+            editor.set_manufacturer_code("aaxe").err(),
+            Some(EditError::InvalidManufacturerCode)
+        );
+    }
+
+    #[test]
+    fn fix_logo() {
+        let mut rom_data = fs::read("test_roms/header_only_test.gb").unwrap();
+        rom_data[0x104..0x134].iter_mut().for_each(|byte| *byte = 0);
+
+        let mut editor = HeaderEditor::new(rom_data).unwrap();
+        editor.fix_logo();
+        assert!(editor.finish().unwrap().is_logo_valid(Model::Dmg));
+    }
+
+    #[test]
+    fn pad() {
+        let mut editor = editor();
+        editor.pad(0xFF).unwrap();
+
+        let header = editor.finish().unwrap();
+        assert_eq!(header.get_rom_data().len(), 0x8000);
+        assert_eq!(header.get_rom_data()[0x7FFF], 0xFF);
+        assert_eq!(header.get_rom_size(), 0x00);
+        assert!(header.verify_rom_size().is_ok());
+    }
+}
@@ -0,0 +1,201 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::cartridge::CartridgeType;
+
+/// Error returned when a ROM image can not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    // The buffer ends before the cartridge header does
+    TooShort { len: usize, required: usize },
+    // The title contains a byte that can not be decoded, at the given ROM offset
+    InvalidTitle { offset: usize, byte: u8 },
+    // The new licensee code contains a byte that can not be decoded, at the given ROM offset
+    InvalidLicenseeCode { offset: usize, byte: u8 },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::TooShort { len, required } => write!(
+                f,
+                "ROM is {} bytes long, at least {} bytes are needed for the header",
+                len, required
+            ),
+            LoadError::InvalidTitle { offset, byte } => write!(
+                f,
+                "invalid byte 0x{:02X} in title at offset 0x{:04X}",
+                byte, offset
+            ),
+            LoadError::InvalidLicenseeCode { offset, byte } => write!(
+                f,
+                "invalid byte 0x{:02X} in new licensee code at offset 0x{:04X}",
+                byte, offset
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for LoadError {}
+
+/// Error returned when a header field can not be written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    // The title does not fit in the space left by the current title layout
+    TitleTooLong { len: usize, max: usize },
+    // The title contains a character outside printable ASCII, at the given index
+    InvalidTitle { index: usize },
+    // A 15 character title ends in 4 characters that read back as a manufacturer code
+    AmbiguousTitle,
+    // The title layout or manufacturer code needs the CGB flag, which is clear
+    CgbFlagNotSet,
+    // The title layout needs a manufacturer code, which is not present
+    MissingManufacturerCode,
+    // The manufacturer code is not 4 uppercase letters or digits
+    InvalidManufacturerCode,
+    // The new licensee code is not 2 printable ASCII characters
+    InvalidLicenseeCode,
+    // The image is larger than the biggest ROM size code can declare
+    RomTooLarge { len: usize, max: usize },
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EditError::TitleTooLong { len, max } => write!(
+                f,
+                "title is {} bytes long, at most {} bytes fit in the header",
+                len, max
+            ),
+            EditError::InvalidTitle { index } => write!(
+                f,
+                "title character at index {} is not printable ASCII",
+                index
+            ),
+            EditError::AmbiguousTitle => {
+                f.write_str("title ends in 4 characters that would be read as a manufacturer code")
+            }
+            EditError::CgbFlagNotSet => f.write_str("the CGB flag is not set"),
+            EditError::MissingManufacturerCode => {
+                f.write_str("the header has no manufacturer code")
+            }
+            EditError::InvalidManufacturerCode => {
+                f.write_str("manufacturer code must be 4 uppercase letters or digits")
+            }
+            EditError::InvalidLicenseeCode => {
+                f.write_str("new licensee code must be 2 printable ASCII characters")
+            }
+            EditError::RomTooLarge { len, max } => write!(
+                f,
+                "ROM is {} bytes long, the largest declarable size is {} bytes",
+                len, max
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for EditError {}
+
+/// Error returned when a ROM can not be mapped into a [`Cartridge`](crate::mappers::Cartridge).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CartridgeError {
+    // No mapper is implemented for the cartridge type
+    UnsupportedMapper { cartridge_type: CartridgeType },
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CartridgeError::UnsupportedMapper { cartridge_type } => {
+                write!(f, "no mapper is implemented for {}", cartridge_type)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for CartridgeError {}
+
+/// Error returned when save data does not fit a cartridge's RAM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError {
+    // The save is not the same size as the cartridge RAM
+    SizeMismatch { len: usize, expected: usize },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::SizeMismatch { len, expected } => write!(
+                f,
+                "save is {} bytes long, the cartridge has {} bytes of RAM",
+                len, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for SaveError {}
+
+/// Error returned when a ROM can not be read from a file or stream.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum ReadError {
+    Io(std::io::Error),
+    Load(LoadError),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "could not read ROM: {}", e),
+            ReadError::Load(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Load(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ReadError {
+    fn from(e: std::io::Error) -> ReadError {
+        ReadError::Io(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<LoadError> for ReadError {
+    fn from(e: LoadError) -> ReadError {
+        ReadError::Load(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = LoadError::InvalidTitle {
+            offset: 0x138,
+            byte: 0xFF,
+        };
+        assert_eq!(
+            error.to_string(),
+            "invalid byte 0xFF in title at offset 0x0138"
+        );
+    }
+}
@@ -0,0 +1,945 @@
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::licensee;
+use crate::view::HeaderView;
+#[cfg(feature = "alloc")]
+use crate::{
+    cartridge::{CartridgeType, Mapper},
+    checksum::{self, ChecksumMismatch},
+    error::LoadError,
+    logo::{self, LogoDiff, Model},
+    region::{self, Destination, RegionGuess},
+    size::{self, RamSize, RamSizeMismatch, RomSize, RomSizeMismatch, ROM_BANK_SIZE},
+    title::{self, TitlePolicy},
+};
+
+pub(crate) const HEADER_END: usize = 0x150;
+
+pub(crate) const TITLE_START: usize = 0x134;
+pub(crate) const MANUFACTURER_CODE_START: usize = 0x13F;
+pub(crate) const CGB_FLAG_ADDRESS: usize = 0x143;
+
+/// Game Boy Color support declared by the flag at 0x0143.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CgbSupport {
+    // Bit 7 clear, the byte is part of the title on older cartridges
+    DmgOnly,
+    // 0x80, runs on both but uses CGB features when available
+    CgbEnhanced,
+    // 0xC0, refuses to run on older hardware
+    CgbOnly,
+    // Bit 7 and bit 2 or 3 set, switches the CGB into the non-CGB PGB mode
+    Pgb,
+}
+
+impl CgbSupport {
+    pub fn from_flag(flag: u8) -> CgbSupport {
+        if flag & 0x80 == 0 {
+            CgbSupport::DmgOnly
+        } else if flag & 0x0C != 0 {
+            CgbSupport::Pgb
+        } else if flag & 0x40 != 0 {
+            CgbSupport::CgbOnly
+        } else {
+            CgbSupport::CgbEnhanced
+        }
+    }
+
+    /// The canonical flag value. PGB mode is written as 0x84.
+    pub fn flag(self) -> u8 {
+        match self {
+            CgbSupport::DmgOnly => 0x00,
+            CgbSupport::CgbEnhanced => 0x80,
+            CgbSupport::CgbOnly => 0xC0,
+            CgbSupport::Pgb => 0x84,
+        }
+    }
+}
+
+/// Whether the Super Game Boy enables its functions for a ROM, and why not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SgbStatus {
+    // 0x0146 is 0x03 and the old licensee code is 0x33
+    Supported,
+    // 0x0146 is 0x00
+    NotRequested,
+    // 0x0146 is neither 0x00 nor 0x03, the SGB only accepts exactly 0x03
+    InvalidFlag { flag: u8 },
+    // 0x0146 is 0x03 but the old licensee code is not 0x33, so the SGB ignores the flag
+    LicenseeNotNew { license_code: u8 },
+}
+
+impl SgbStatus {
+    pub fn new(sgb_flag: u8, license_code: u8) -> SgbStatus {
+        match sgb_flag {
+            0x00 => SgbStatus::NotRequested,
+            0x03 if license_code == licensee::USE_NEW_LICENSEE_CODE => SgbStatus::Supported,
+            0x03 => SgbStatus::LicenseeNotNew { license_code },
+            flag => SgbStatus::InvalidFlag { flag },
+        }
+    }
+
+    pub fn is_supported(self) -> bool {
+        self == SgbStatus::Supported
+    }
+}
+
+impl fmt::Display for SgbStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SgbStatus::Supported => f.write_str("SGB functions enabled"),
+            SgbStatus::NotRequested => f.write_str("SGB functions not requested"),
+            SgbStatus::InvalidFlag { flag } => write!(
+                f,
+                "SGB flag is 0x{:02X}, the SGB only enables its functions for 0x03",
+                flag
+            ),
+            SgbStatus::LicenseeNotNew { license_code } => write!(
+                f,
+                "SGB flag is set but the old licensee code is 0x{:02X}, the SGB ignores the flag unless it is 0x33",
+                license_code
+            ),
+        }
+    }
+}
+
+/// How the 16 bytes at 0x0134 - 0x0143 are split between the title and later additions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TitleLayout {
+    // 16 character title : 0x0134 - 0x0143
+    Legacy,
+    // 15 character title followed by the CGB flag : 0x0134 - 0x0142
+    Cgb,
+    // 11 character title, 4 character manufacturer code and the CGB flag : 0x0134 - 0x013E
+    CgbWithManufacturerCode,
+}
+
+impl TitleLayout {
+    /// Guesses the layout from the header. The manufacturer code is only assumed when the
+    /// CGB flag is set and 0x013F - 0x0142 are four uppercase letters or digits, which
+    /// misreads 15 character titles ending in four such characters.
+    pub fn detect(header: &HeaderView) -> TitleLayout {
+        header.title_layout()
+    }
+
+    // Layout from the CGB flag and the 4 bytes that may hold a manufacturer code
+    pub(crate) fn from_fields(cgb_flag: u8, code: &[u8]) -> TitleLayout {
+        if CgbSupport::from_flag(cgb_flag) == CgbSupport::DmgOnly {
+            return TitleLayout::Legacy;
+        }
+
+        if code
+            .iter()
+            .all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+        {
+            TitleLayout::CgbWithManufacturerCode
+        } else {
+            TitleLayout::Cgb
+        }
+    }
+
+    /// End (exclusive) of the title bytes.
+    pub fn title_end(self) -> usize {
+        match self {
+            TitleLayout::Legacy => CGB_FLAG_ADDRESS + 1,
+            TitleLayout::Cgb => CGB_FLAG_ADDRESS,
+            TitleLayout::CgbWithManufacturerCode => MANUFACTURER_CODE_START,
+        }
+    }
+}
+
+/// Options controlling how [`DMG::with_options`] parses a ROM.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LoadOptions {
+    pub title_policy: TitlePolicy,
+}
+
+/// Cartridge header of a Game Boy ROM, located at 0x0100 - 0x014F.
+#[cfg(feature = "alloc")]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct DMG<S = Vec<u8>> {
+    entry_point: u16,                    // Entry point of the ROM which is always 0x0100
+    nintendo_logo: [u8; logo::LOGO_LEN], // Nintendo logo as uint8_t array of size 0x30 : 0x0104 - 0x0133
+    title: String, // Title of the game as ASCII, without NUL padding : 0x0134 - 0x0143, 0x0142 or 0x013E depending on the layout
+    title_end: usize, // End (exclusive) of the title bytes, before any NUL padding
+    title_layout: TitleLayout, // Which of 0x013F - 0x0143 belong to the title
+    manufacturer_code: String, // Manufacturer code, only set with TitleLayout::CgbWithManufacturerCode : 0x013F - 0x0142
+    cgb_flag: u8, // 0x80 - CGB enhanced, 0xC0 - CGB only, otherwise usually part of the title : 0x0143
+    new_license_code: String, // New license code used on games released after SGB. Only set if m_licenseCode == 0x33 : 0x0144 - 0x0145
+    sgb_flag: u8, // 0x00 - No SGB functionality, 0x03 - Game supports SGB functionality : 0x0146
+    cartridge_type: u8, // Specifies which external cartridge exists in the cartridge (eg. Memory Bank Controller) : 0x0147
+    rom_size: u8,       // Specifies the ROM size of the cartridge. Calculated as 32KB << N : 0x0148
+    ram_size: u8, // Size of external RAM in cartridge (if any). Must be 0x00 for MBC2 : 0x0149
+    destination_code: u8, // 0x00 == Japanese, 0x01 == Non-Japanese : 0x014A
+    license_code: u8, // Single byte license code. A value of 0x33 points to the use of m_newLicenseCode : 0x014B
+    mask_rom_version_number: u8, // Version number of the game, usually 0x00 : 0x014C
+    header_checksum: u8, // Checksum across bytes 0x0134 - 0x014C, the game won't work if the checksum is incorrect : 0x014D
+    computed_header_checksum: u8, // Header checksum computed from 0x0134 - 0x014C while loading
+    global_checksum: u16, // Checksum calculated by adding all bytes of the cartridge, except the two checksum bytes : 0x014E - 0x014F
+    rom_data: S, // The whole ROM image the header was read from, e.g. a Vec<u8> or a memory map
+}
+
+#[cfg(feature = "alloc")]
+impl<S: AsRef<[u8]>> fmt::Debug for DMG<S> {
+    // The ROM image is left out, it can be several megabytes long
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DMG")
+            .field("title", &self.title)
+            .field("manufacturer_code", &self.manufacturer_code)
+            .field("cgb_flag", &self.cgb_flag)
+            .field("new_license_code", &self.new_license_code)
+            .field("sgb_flag", &self.sgb_flag)
+            .field("cartridge_type", &self.cartridge_type)
+            .field("rom_size", &self.rom_size)
+            .field("ram_size", &self.ram_size)
+            .field("destination_code", &self.destination_code)
+            .field("license_code", &self.license_code)
+            .field("mask_rom_version_number", &self.mask_rom_version_number)
+            .field("header_checksum", &self.header_checksum)
+            .field("global_checksum", &self.global_checksum)
+            .field("rom_len", &self.get_rom_data().len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: AsRef<[u8]>> DMG<S> {
+    /// Parses the header with the default [`LoadOptions`], which never fail because of the title.
+    pub fn new(rom_data: S) -> Result<DMG<S>, LoadError> {
+        DMG::with_options(rom_data, LoadOptions::default())
+    }
+
+    pub fn with_options(rom_data: S, options: LoadOptions) -> Result<DMG<S>, LoadError> {
+        let view = HeaderView::new(rom_data.as_ref())?;
+        let title_layout = view.title_layout();
+        let title_end = TITLE_START + view.title_bytes().len();
+        let title = title::decode_title(view.title_bytes(), TITLE_START, options.title_policy)?;
+        let manufacturer_code = view.manufacturer_code().unwrap_or("").to_string();
+        let new_license_code = match view.new_license_code() {
+            Some(code) => code.to_string(),
+            None if view.get_license_code() == licensee::USE_NEW_LICENSEE_CODE => {
+                // The view only rejects a code with a non-ASCII byte
+                let offset = (0x144..0x146)
+                    .find(|&offset| !rom_data.as_ref()[offset].is_ascii())
+                    .unwrap();
+                return Err(LoadError::InvalidLicenseeCode {
+                    offset,
+                    byte: rom_data.as_ref()[offset],
+                });
+            }
+            None => "".to_string(),
+        };
+
+        Ok(DMG {
+            entry_point: 0x100,
+            nintendo_logo: *view.get_nintendo_logo(),
+            title,
+            title_end,
+            title_layout,
+            manufacturer_code,
+            cgb_flag: view.get_cgb_flag(),
+            sgb_flag: view.get_sgb_flag(),
+            cartridge_type: view.get_cartridge_type(),
+            rom_size: view.get_rom_size(),
+            ram_size: if view.cartridge_type().mapper() != Mapper::Mbc2 {
+                view.get_ram_size()
+            } else {
+                0
+            },
+            destination_code: view.get_destination_code(),
+            license_code: view.get_license_code(),
+            mask_rom_version_number: view.get_mask_romversion_number(),
+            header_checksum: view.get_header_checksum(),
+            computed_header_checksum: view.computed_header_checksum(),
+            global_checksum: view.get_global_checksum(),
+            new_license_code,
+            rom_data,
+        })
+    }
+
+    /// Borrowed view of the header, for code that works with both owned and borrowed ROMs.
+    pub fn header_view(&self) -> HeaderView<'_> {
+        // The length was checked when loading
+        HeaderView::new(self.get_rom_data()).unwrap()
+    }
+
+    pub fn get_entry_point(&self) -> u16 {
+        self.entry_point
+    }
+
+    pub fn get_nintendo_logo(&self) -> &[u8; logo::LOGO_LEN] {
+        &self.nintendo_logo
+    }
+
+    /// Bytes of the logo that differ from the one expected by the boot ROM.
+    pub fn logo_diff(&self) -> LogoDiff {
+        LogoDiff::new(&self.nintendo_logo)
+    }
+
+    /// Whether the boot ROM of `model` would accept the logo.
+    pub fn is_logo_valid(&self, model: Model) -> bool {
+        self.logo_diff().is_bootable(model)
+    }
+
+    pub fn get_title(&self) -> &String {
+        &self.title
+    }
+
+    /// The undecoded title bytes, without NUL padding.
+    pub fn title_bytes(&self) -> &[u8] {
+        &self.get_rom_data()[TITLE_START..self.title_end]
+    }
+
+    pub fn title_layout(&self) -> TitleLayout {
+        self.title_layout
+    }
+
+    /// The 4 character manufacturer code used by later CGB cartridges, if present.
+    pub fn manufacturer_code(&self) -> Option<&str> {
+        if self.manufacturer_code.is_empty() {
+            None
+        } else {
+            Some(&self.manufacturer_code)
+        }
+    }
+
+    pub fn get_cgb_flag(&self) -> u8 {
+        self.cgb_flag
+    }
+
+    pub fn cgb_support(&self) -> CgbSupport {
+        CgbSupport::from_flag(self.cgb_flag)
+    }
+
+    pub fn get_sgb_flag(&self) -> u8 {
+        self.sgb_flag
+    }
+
+    /// Whether the Super Game Boy enables its functions, which requires both the SGB flag
+    /// and the 0x33 old licensee code.
+    pub fn supports_sgb(&self) -> bool {
+        self.sgb_status().is_supported()
+    }
+
+    /// Explains how the Super Game Boy treats the SGB flag.
+    pub fn sgb_status(&self) -> SgbStatus {
+        SgbStatus::new(self.sgb_flag, self.license_code)
+    }
+
+    pub fn get_cartridge_type(&self) -> u8 {
+        self.cartridge_type
+    }
+
+    pub fn cartridge_type(&self) -> CartridgeType {
+        CartridgeType::from_code(self.cartridge_type)
+    }
+
+    pub fn get_rom_size(&self) -> u8 {
+        self.rom_size
+    }
+
+    pub fn rom_size(&self) -> RomSize {
+        RomSize::from_code(self.rom_size)
+    }
+
+    /// Checks the declared ROM size against the length of the loaded image.
+    pub fn verify_rom_size(&self) -> Result<(), RomSizeMismatch> {
+        size::verify_rom_size(self.rom_size(), self.get_rom_data().len())
+    }
+
+    /// Number of 16 KiB banks in the loaded image, counting a trailing partial bank.
+    pub fn rom_bank_count(&self) -> usize {
+        self.get_rom_data().len().div_ceil(ROM_BANK_SIZE)
+    }
+
+    /// The 16 KiB bank `index` of the loaded image. The last bank may be shorter if the
+    /// image is not a multiple of the bank size.
+    pub fn rom_bank(&self, index: usize) -> Option<&[u8]> {
+        let start = index.checked_mul(ROM_BANK_SIZE)?;
+        let rom_data = self.get_rom_data();
+        if start >= rom_data.len() {
+            return None;
+        }
+        let end = (start + ROM_BANK_SIZE).min(rom_data.len());
+        Some(&rom_data[start..end])
+    }
+
+    pub fn get_ram_size(&self) -> u8 {
+        self.ram_size
+    }
+
+    pub fn ram_size(&self) -> RamSize {
+        self.header_view().ram_size()
+    }
+
+    /// Checks the RAM size declared at 0x0149 against the cartridge type.
+    pub fn verify_ram_size(&self) -> Result<(), RamSizeMismatch> {
+        self.header_view().verify_ram_size()
+    }
+
+    pub fn get_destination_code(&self) -> u8 {
+        self.destination_code
+    }
+
+    pub fn destination(&self) -> Destination {
+        Destination::from_code(self.destination_code)
+    }
+
+    /// Best-guess release region from the manufacturer code, destination code and licensee.
+    pub fn region(&self) -> RegionGuess {
+        region::guess_region(
+            self.manufacturer_code(),
+            self.destination(),
+            self.license_code,
+            &self.new_license_code,
+        )
+    }
+
+    pub fn get_license_code(&self) -> u8 {
+        self.license_code
+    }
+
+    pub fn get_mask_romversion_number(&self) -> u8 {
+        self.mask_rom_version_number
+    }
+
+    pub fn get_header_checksum(&self) -> u8 {
+        self.header_checksum
+    }
+
+    /// Header checksum computed from 0x0134 - 0x014C, regardless of the stored value.
+    pub fn computed_header_checksum(&self) -> u8 {
+        self.computed_header_checksum
+    }
+
+    /// Checks the stored header checksum against the computed one. The boot ROM locks up
+    /// on real hardware when they differ.
+    pub fn verify_header_checksum(&self) -> Result<(), ChecksumMismatch<u8>> {
+        if self.header_checksum == self.computed_header_checksum {
+            Ok(())
+        } else {
+            Err(ChecksumMismatch {
+                stored: self.header_checksum,
+                computed: self.computed_header_checksum,
+            })
+        }
+    }
+
+    pub fn get_global_checksum(&self) -> u16 {
+        self.global_checksum
+    }
+
+    pub fn get_new_license_code(&self) -> &String {
+        &self.new_license_code
+    }
+
+    /// Publisher name from the licensee code, following the 0x33 indirection to the
+    /// new licensee code.
+    pub fn publisher(&self) -> Option<&'static str> {
+        licensee::publisher(self.license_code, &self.new_license_code)
+    }
+
+    pub fn get_rom_data(&self) -> &[u8] {
+        self.rom_data.as_ref()
+    }
+
+    pub fn into_rom_data(self) -> S {
+        self.rom_data
+    }
+
+    /// Global checksum computed over the whole ROM image, regardless of the stored value.
+    pub fn computed_global_checksum(&self) -> u16 {
+        checksum::global_checksum(self.get_rom_data())
+    }
+
+    /// Checks the stored global checksum against the computed one. The boot ROM ignores it,
+    /// but a mismatch on a retail game usually means a bad dump.
+    pub fn verify_global_checksum(&self) -> Result<(), ChecksumMismatch<u16>> {
+        let computed = self.computed_global_checksum();
+        if self.global_checksum == computed {
+            Ok(())
+        } else {
+            Err(ChecksumMismatch {
+                stored: self.global_checksum,
+                computed,
+            })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::region::Region;
+    use std::fs::File;
+    use std::io::*;
+
+    fn read_rom() -> Vec<u8> {
+        let mut file = File::open("test_roms/header_only_test.gb").unwrap();
+        let mut buffer = vec![];
+        file.read_to_end(&mut buffer).unwrap();
+        buffer
+    }
+
+    fn load_rom() -> DMG {
+        DMG::new(read_rom()).unwrap()
+    }
+
+    #[test]
+    fn new() {
+        let mut file = File::open("test_roms/header_only_test.gb").unwrap();
+        let mut buffer = vec![];
+        file.read_to_end(&mut buffer).unwrap();
+
+        assert!(DMG::new(buffer).is_ok());
+    }
+
+    #[test]
+    fn clone_and_debug() {
+        let header = load_rom();
+        assert_eq!(header.clone(), header);
+
+        let debug = format!("{:?}", header);
+        assert!(debug.contains("title: \"GBLOADERTEST1234\""));
+        assert!(debug.contains("rom_len: 336"));
+    }
+
+    #[test]
+    fn new_too_short() {
+        let buffer = vec![0; 0x14F];
+
+        assert_eq!(
+            DMG::new(buffer).err(),
+            Some(LoadError::TooShort {
+                len: 0x14F,
+                required: 0x150
+            })
+        );
+    }
+
+    #[test]
+    fn new_empty() {
+        assert!(DMG::new(vec![]).is_err());
+    }
+
+    #[test]
+    fn new_invalid_title() {
+        let mut buffer = read_rom();
+        buffer[0x138] = 0xFF;
+
+        let options = LoadOptions {
+            title_policy: TitlePolicy::StrictAscii,
+        };
+        assert_eq!(
+            DMG::with_options(buffer, options).err(),
+            Some(LoadError::InvalidTitle {
+                offset: 0x138,
+                byte: 0xFF
+            })
+        );
+    }
+
+    #[test]
+    fn new_lossy_title() {
+        let mut buffer = read_rom();
+        buffer[0x138] = 0xFF;
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.get_title(), "GBLO\u{FFFD}DERTEST1234");
+        assert_eq!(header.title_bytes(), b"GBLO\xFFDERTEST1234");
+    }
+
+    #[test]
+    fn new_jis_x0201_title() {
+        let mut buffer = read_rom();
+        buffer[0x134..0x144].copy_from_slice(b"\xCE\xDF\xB9\xD3\xDD\0\0\0\0\0\0\0\0\0\0\0");
+
+        let options = LoadOptions {
+            title_policy: TitlePolicy::JisX0201,
+        };
+        let header = DMG::with_options(buffer, options).unwrap();
+        assert_eq!(
+            header.get_title(),
+            "\u{FF8E}\u{FF9F}\u{FF79}\u{FF93}\u{FF9D}"
+        );
+    }
+
+    #[test]
+    fn new_invalid_license_code() {
+        let mut buffer = read_rom();
+        buffer[0x145] = 0x80;
+
+        assert_eq!(
+            DMG::new(buffer).err(),
+            Some(LoadError::InvalidLicenseeCode {
+                offset: 0x145,
+                byte: 0x80
+            })
+        );
+    }
+
+    #[test]
+    fn new_utf8_license_code() {
+        let mut buffer = read_rom();
+        buffer[0x144..0x146].copy_from_slice("é".as_bytes());
+
+        assert_eq!(
+            DMG::new(buffer).err(),
+            Some(LoadError::InvalidLicenseeCode {
+                offset: 0x144,
+                byte: 0xC3
+            })
+        );
+    }
+
+    #[test]
+    fn get_entry_point() {
+        let header = load_rom();
+        assert_eq!(header.get_entry_point(), 0x0100);
+    }
+
+    #[test]
+    fn get_title() {
+        let header = load_rom();
+        assert_eq!(header.get_title(), "GBLOADERTEST1234");
+    }
+
+    #[test]
+    fn get_title_cgb() {
+        let mut buffer = read_rom();
+        buffer[0x134..0x144].copy_from_slice(b"TETRIS DX\0\0\0\0\0\0\x80");
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.title_layout(), TitleLayout::Cgb);
+        assert_eq!(header.get_title(), "TETRIS DX");
+        assert_eq!(header.manufacturer_code(), None);
+        assert_eq!(header.cgb_support(), CgbSupport::CgbEnhanced);
+    }
+
+    #[test]
+    fn get_title_manufacturer_code() {
+        let mut buffer = read_rom();
+        buffer[0x134..0x144].copy_from_slice(b"POKEMON_SLVAAXE\x80");
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.title_layout(), TitleLayout::CgbWithManufacturerCode);
+        assert_eq!(header.get_title(), "POKEMON_SLV");
+        assert_eq!(header.manufacturer_code(), Some("AAXE"));
+    }
+
+    #[test]
+    fn detect_title_layout() {
+        let rom_data = read_rom();
+        let view = HeaderView::new(&rom_data).unwrap();
+        assert_eq!(TitleLayout::detect(&view), TitleLayout::Legacy);
+    }
+
+    #[test]
+    fn get_title_legacy_padding() {
+        let mut buffer = read_rom();
+        buffer[0x134..0x144].copy_from_slice(b"TETRIS\0\0\0\0\0\0\0\0\0\0");
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.title_layout(), TitleLayout::Legacy);
+        assert_eq!(header.get_title(), "TETRIS");
+        assert_eq!(header.cgb_support(), CgbSupport::DmgOnly);
+    }
+
+    #[test]
+    fn cgb_support() {
+        let header = load_rom();
+        assert_eq!(header.get_cgb_flag(), b'4');
+        assert_eq!(header.cgb_support(), CgbSupport::DmgOnly);
+
+        assert_eq!(CgbSupport::from_flag(0xC0), CgbSupport::CgbOnly);
+        assert_eq!(CgbSupport::from_flag(0x84), CgbSupport::Pgb);
+        assert_eq!(CgbSupport::from_flag(0x88), CgbSupport::Pgb);
+    }
+
+    #[test]
+    fn get_new_license_code() {
+        let header = load_rom();
+        assert_eq!(header.get_new_license_code(), "01");
+    }
+
+    #[test]
+    fn publisher() {
+        let header = load_rom();
+        assert_eq!(header.publisher(), Some("Nintendo"));
+
+        let mut buffer = read_rom();
+        buffer[0x14B] = 0x08;
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.publisher(), Some("Capcom"));
+    }
+
+    #[test]
+    fn get_sgb_flag() {
+        let header = load_rom();
+        assert_eq!(header.get_sgb_flag(), 0x03);
+    }
+
+    #[test]
+    fn supports_sgb() {
+        let header = load_rom();
+        assert!(header.supports_sgb());
+        assert_eq!(header.sgb_status(), SgbStatus::Supported);
+    }
+
+    #[test]
+    fn supports_sgb_old_licensee() {
+        let mut buffer = read_rom();
+        buffer[0x14B] = 0x01;
+
+        let header = DMG::new(buffer).unwrap();
+        assert!(!header.supports_sgb());
+        assert_eq!(
+            header.sgb_status(),
+            SgbStatus::LicenseeNotNew { license_code: 0x01 }
+        );
+        assert_eq!(
+            header.sgb_status().to_string(),
+            "SGB flag is set but the old licensee code is 0x01, the SGB ignores the flag unless it is 0x33"
+        );
+    }
+
+    #[test]
+    fn sgb_status() {
+        assert_eq!(SgbStatus::new(0x00, 0x33), SgbStatus::NotRequested);
+        assert_eq!(
+            SgbStatus::new(0x01, 0x33),
+            SgbStatus::InvalidFlag { flag: 0x01 }
+        );
+    }
+
+    #[test]
+    fn get_cartridge_type() {
+        let header = load_rom();
+        assert_eq!(header.get_cartridge_type(), 0x01);
+    }
+
+    #[test]
+    fn cartridge_type() {
+        let header = load_rom();
+        assert_eq!(header.cartridge_type(), CartridgeType::Mbc1);
+    }
+
+    #[test]
+    fn get_rom_size() {
+        let header = load_rom();
+        assert_eq!(header.get_rom_size(), 0x02);
+    }
+
+    #[test]
+    fn rom_size() {
+        let header = load_rom();
+        assert_eq!(header.rom_size().bytes(), Some(128 * 1024));
+        assert_eq!(header.rom_size().banks(), Some(8));
+    }
+
+    #[test]
+    fn verify_rom_size() {
+        let header = load_rom();
+        assert_eq!(
+            header.verify_rom_size(),
+            Err(RomSizeMismatch::Underdump {
+                declared: 0x20000,
+                actual: 0x150
+            })
+        );
+
+        let mut buffer = read_rom();
+        buffer.resize(0x20000, 0xFF);
+        let header = DMG::new(buffer).unwrap();
+        assert!(header.verify_rom_size().is_ok());
+    }
+
+    #[test]
+    fn rom_bank() {
+        let mut buffer = read_rom();
+        buffer.resize(0x8000, 0);
+        buffer[0x4000] = 0x42;
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.rom_bank_count(), 2);
+        assert_eq!(header.rom_bank(0).unwrap()[0x100], 0x00);
+        assert_eq!(header.rom_bank(1).unwrap()[0], 0x42);
+        assert_eq!(header.rom_bank(1).unwrap().len(), 0x4000);
+        assert_eq!(header.rom_bank(2), None);
+    }
+
+    #[test]
+    fn get_ram_size() {
+        let header = load_rom();
+        assert_eq!(header.get_ram_size(), 0x03);
+    }
+
+    #[test]
+    fn ram_size() {
+        let header = load_rom();
+        assert_eq!(header.ram_size(), RamSize::Kib32);
+        assert_eq!(
+            header.verify_ram_size(),
+            Err(RamSizeMismatch::DeclaredWithoutRam {
+                cartridge_type: CartridgeType::Mbc1,
+                code: 0x03
+            })
+        );
+    }
+
+    #[test]
+    fn ram_size_mbc2_battery() {
+        let mut buffer = read_rom();
+        buffer[0x147] = 0x06;
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.get_ram_size(), 0);
+        assert_eq!(header.ram_size(), RamSize::Mbc2);
+        assert!(header.verify_ram_size().is_err());
+    }
+
+    #[test]
+    fn get_destination_code() {
+        let header = load_rom();
+        assert_eq!(header.get_destination_code(), 0x01);
+    }
+
+    #[test]
+    fn destination() {
+        let header = load_rom();
+        assert_eq!(header.destination(), Destination::Overseas);
+        assert_eq!(header.region().region, Region::Overseas);
+    }
+
+    #[test]
+    fn region_manufacturer_code() {
+        let mut buffer = read_rom();
+        buffer[0x134..0x144].copy_from_slice(b"POKEMON_SLVAAXJ\x80");
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.region().region, Region::Japan);
+    }
+
+    #[test]
+    fn get_license_code() {
+        let header = load_rom();
+        assert_eq!(header.get_license_code(), 0x33);
+    }
+
+    #[test]
+    fn get_mask_romversion_number() {
+        let header = load_rom();
+        assert_eq!(header.get_mask_romversion_number(), 0);
+    }
+
+    #[test]
+    fn get_header_checksum() {
+        let header = load_rom();
+        assert_eq!(header.get_header_checksum(), 0xFF);
+    }
+
+    #[test]
+    fn verify_header_checksum() {
+        let header = load_rom();
+        assert_eq!(header.computed_header_checksum(), 0xFF);
+        assert!(header.verify_header_checksum().is_ok());
+    }
+
+    #[test]
+    fn verify_header_checksum_mismatch() {
+        let mut buffer = read_rom();
+        buffer[0x14D] = 0x12;
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(
+            header.verify_header_checksum(),
+            Err(ChecksumMismatch {
+                stored: 0x12,
+                computed: 0xFF
+            })
+        );
+    }
+
+    #[test]
+    fn get_global_checksum() {
+        let header = load_rom();
+        assert_eq!(header.get_global_checksum(), 0);
+    }
+
+    #[test]
+    fn get_rom_data() {
+        let header = load_rom();
+        assert_eq!(header.get_rom_data(), &read_rom()[..]);
+        assert_eq!(header.into_rom_data(), read_rom());
+    }
+
+    #[test]
+    fn verify_global_checksum() {
+        let header = load_rom();
+        assert_eq!(header.computed_global_checksum(), 0x1669);
+        assert_eq!(
+            header.verify_global_checksum(),
+            Err(ChecksumMismatch {
+                stored: 0,
+                computed: 0x1669
+            })
+        );
+    }
+
+    #[test]
+    fn verify_global_checksum_fixed() {
+        let mut buffer = read_rom();
+        checksum::fix_global_checksum(&mut buffer).unwrap();
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(header.get_global_checksum(), 0x1669);
+        assert!(header.verify_global_checksum().is_ok());
+    }
+
+    #[test]
+    fn get_nintendo_logo() {
+        let nintendo_logo_reference = vec![
+            0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0C,
+            0x00, 0x0D, 0x00, 0x08, 0x11, 0x1F, 0x88, 0x89, 0x00, 0x0E, 0xDC, 0xCC, 0x6E, 0xE6,
+            0xDD, 0xDD, 0xD9, 0x99, 0xBB, 0xBB, 0x67, 0x63, 0x6E, 0x0E, 0xEC, 0xCC, 0xDD, 0xDC,
+            0x99, 0x9F, 0xBB, 0xB9, 0x33, 0x3E,
+        ];
+
+        let header = load_rom();
+        let nintendo_logo = header.get_nintendo_logo();
+
+        assert_eq!(&nintendo_logo_reference, nintendo_logo);
+    }
+
+    #[test]
+    fn is_logo_valid() {
+        let header = load_rom();
+        assert!(header.logo_diff().is_empty());
+        assert!(header.is_logo_valid(Model::Dmg));
+        assert!(header.is_logo_valid(Model::Cgb));
+    }
+
+    #[test]
+    fn is_logo_valid_last_byte() {
+        let mut buffer = read_rom();
+        buffer[0x133] = 0;
+
+        let header = DMG::new(buffer).unwrap();
+        assert_eq!(
+            header.logo_diff().addresses().collect::<Vec<_>>(),
+            vec![0x133]
+        );
+        assert!(!header.is_logo_valid(Model::Dmg));
+        assert!(header.is_logo_valid(Model::Cgb));
+    }
+}
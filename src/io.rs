@@ -0,0 +1,146 @@
+//! Loading ROMs from files and streams.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::{LoadError, ReadError};
+use crate::header::{LoadOptions, DMG, HEADER_END};
+use crate::view::{OwnedHeader, HEADER_LEN, HEADER_START};
+
+impl DMG {
+    /// Reads and parses the whole ROM image at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<DMG, ReadError> {
+        DMG::from_reader(File::open(path)?)
+    }
+
+    /// Reads `reader` to the end and parses the whole ROM image.
+    pub fn from_reader<R: Read>(reader: R) -> Result<DMG, ReadError> {
+        DMG::from_reader_with_options(reader, LoadOptions::default())
+    }
+
+    pub fn from_reader_with_options<R: Read>(
+        mut reader: R,
+        options: LoadOptions,
+    ) -> Result<DMG, ReadError> {
+        let mut rom_data = vec![];
+        reader.read_to_end(&mut rom_data)?;
+        Ok(DMG::with_options(rom_data, options)?)
+    }
+}
+
+impl OwnedHeader {
+    /// Reads only the header of the ROM at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<OwnedHeader, ReadError> {
+        OwnedHeader::from_reader(File::open(path)?)
+    }
+
+    /// Seeks to 0x0100 and reads only the 0x50 header bytes, leaving the stream positioned
+    /// at 0x0150.
+    ///
+    /// The length of the stream is not looked up, so a stream ending before 0x0100 is reported
+    /// as [`LoadError::TooShort`] with a length of 0x0100.
+    pub fn from_reader<R: Read + Seek>(mut reader: R) -> Result<OwnedHeader, ReadError> {
+        let mut header = [0; HEADER_LEN];
+        let mut read = 0;
+        reader.seek(SeekFrom::Start(HEADER_START as u64))?;
+        while read < HEADER_LEN {
+            match reader.read(&mut header[read..]) {
+                Ok(0) => {
+                    return Err(ReadError::Load(LoadError::TooShort {
+                        len: HEADER_START + read,
+                        required: HEADER_END,
+                    }))
+                }
+                Ok(n) => read += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(ReadError::Io(e)),
+            }
+        }
+        Ok(OwnedHeader::new(header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Cursor};
+
+    const FIXTURE: &str = "test_roms/header_only_test.gb";
+
+    // Counts the bytes read through it
+    struct CountingReader<R> {
+        inner: R,
+        read: usize,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let read = self.inner.read(buf)?;
+            self.read += read;
+            Ok(read)
+        }
+    }
+
+    impl<R: Seek> Seek for CountingReader<R> {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn from_path() {
+        let header = DMG::from_path(FIXTURE).unwrap();
+        assert_eq!(header.get_title(), "GBLOADERTEST1234");
+    }
+
+    #[test]
+    fn from_path_missing() {
+        match DMG::from_path("test_roms/missing.gb") {
+            Err(ReadError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_reader_too_short() {
+        match DMG::from_reader(Cursor::new(vec![0; 0x100])) {
+            Err(ReadError::Load(LoadError::TooShort { len: 0x100, .. })) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn owned_header_reads_only_header() {
+        let mut rom_data = std::fs::read(FIXTURE).unwrap();
+        rom_data.resize(0x100000, 0xFF);
+
+        let mut reader = CountingReader {
+            inner: Cursor::new(rom_data),
+            read: 0,
+        };
+        let header = OwnedHeader::from_reader(&mut reader).unwrap();
+        assert_eq!(reader.read, 0x50);
+        assert_eq!(reader.inner.position(), 0x150);
+        assert_eq!(header.view().title(), Some("GBLOADERTEST1234"));
+        assert_eq!(header.view().rom_size().banks(), Some(8));
+    }
+
+    #[test]
+    fn owned_header_from_path() {
+        let header = OwnedHeader::from_path(FIXTURE).unwrap();
+        assert!(header.view().verify_header_checksum().is_ok());
+    }
+
+    #[test]
+    fn owned_header_too_short() {
+        match OwnedHeader::from_reader(Cursor::new(vec![0; 0x120])) {
+            Err(ReadError::Load(LoadError::TooShort { len: 0x120, .. })) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        match OwnedHeader::from_reader(Cursor::new(vec![0; 0x20])) {
+            Err(ReadError::Load(LoadError::TooShort { len: 0x100, .. })) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
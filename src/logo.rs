@@ -0,0 +1,113 @@
+//! The Nintendo logo bitmap at 0x0104 - 0x0133 and the boot ROM check performed on it.
+
+pub const LOGO_START: usize = 0x104;
+pub const LOGO_END: usize = 0x134;
+pub const LOGO_LEN: usize = LOGO_END - LOGO_START;
+
+/// The logo bitmap expected by the boot ROM.
+pub const NINTENDO_LOGO: [u8; LOGO_LEN] = [
+    0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0C, 0x00, 0x0D,
+    0x00, 0x08, 0x11, 0x1F, 0x88, 0x89, 0x00, 0x0E, 0xDC, 0xCC, 0x6E, 0xE6, 0xDD, 0xDD, 0xD9, 0x99,
+    0xBB, 0xBB, 0x67, 0x63, 0x6E, 0x0E, 0xEC, 0xCC, 0xDD, 0xDC, 0x99, 0x9F, 0xBB, 0xB9, 0x33, 0x3E,
+];
+
+/// Hardware whose boot ROM checks the logo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Model {
+    // Checks all 48 bytes
+    Dmg,
+    // Only checks the first 24 bytes, the top half of the logo
+    Cgb,
+}
+
+impl Model {
+    fn checked_len(self) -> usize {
+        match self {
+            Model::Dmg => LOGO_LEN,
+            Model::Cgb => LOGO_LEN / 2,
+        }
+    }
+}
+
+/// Set of logo bytes that differ from [`NINTENDO_LOGO`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LogoDiff {
+    mask: u64, // Bit N is set when logo byte N differs
+}
+
+impl LogoDiff {
+    pub fn new(logo: &[u8; LOGO_LEN]) -> LogoDiff {
+        let mask = logo
+            .iter()
+            .zip(NINTENDO_LOGO.iter())
+            .enumerate()
+            .filter(|&(_, (a, b))| a != b)
+            .fold(0u64, |mask, (index, _)| mask | 1 << index);
+
+        LogoDiff { mask }
+    }
+
+    /// True when the whole logo matches.
+    pub fn is_empty(&self) -> bool {
+        self.mask == 0
+    }
+
+    /// Number of differing bytes.
+    pub fn count(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    /// Whether the boot ROM of `model` accepts the logo.
+    pub fn is_bootable(&self, model: Model) -> bool {
+        self.mask & ((1u64 << model.checked_len()) - 1) == 0
+    }
+
+    /// Indices (0 - 47) of the differing bytes, relative to the start of the logo at 0x0104.
+    pub fn indices(&self) -> impl Iterator<Item = usize> {
+        let mask = self.mask;
+        (0..LOGO_LEN).filter(move |&index| mask & (1 << index) != 0)
+    }
+
+    /// ROM addresses of the differing bytes.
+    pub fn addresses(&self) -> impl Iterator<Item = usize> {
+        self.indices().map(|index| LOGO_START + index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_identical() {
+        let diff = LogoDiff::new(&NINTENDO_LOGO);
+        assert!(diff.is_empty());
+        assert!(diff.is_bootable(Model::Dmg));
+        assert!(diff.is_bootable(Model::Cgb));
+    }
+
+    #[test]
+    fn diff_bottom_half() {
+        let mut logo = NINTENDO_LOGO;
+        logo[30] = 0;
+        logo[47] = 0;
+
+        let diff = LogoDiff::new(&logo);
+        assert_eq!(diff.count(), 2);
+        assert_eq!(diff.indices().collect::<Vec<_>>(), vec![30, 47]);
+        assert_eq!(diff.addresses().collect::<Vec<_>>(), vec![0x122, 0x133]);
+        assert!(!diff.is_bootable(Model::Dmg));
+        assert!(diff.is_bootable(Model::Cgb));
+    }
+
+    #[test]
+    fn diff_top_half() {
+        let mut logo = NINTENDO_LOGO;
+        logo[0] = 0;
+
+        let diff = LogoDiff::new(&logo);
+        assert!(!diff.is_bootable(Model::Dmg));
+        assert!(!diff.is_bootable(Model::Cgb));
+    }
+}
@@ -0,0 +1,301 @@
+//! MBC1, the memory bank controller of most early cartridges.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{ram_index, rom_bank_mask, rom_byte, Cartridge, OPEN_BUS, RAM_END, RAM_START};
+use crate::logo::{LOGO_END, LOGO_START, NINTENDO_LOGO};
+use crate::size::ROM_BANK_SIZE;
+
+/// Size of the ROM on MBC1M multicart boards.
+pub const MULTICART_LEN: usize = 0x100000;
+/// Number of ROM banks in each game of a multicart.
+pub const MULTICART_GAME_BANKS: usize = 0x10;
+
+/// Guesses whether an MBC1 image is a multicart, whose boards wire the secondary register to
+/// bits 4 - 5 of the ROM bank instead of 5 - 6.
+///
+/// The header can not tell the two apart, so this looks for the Nintendo logo at the start of
+/// another game in a 1 MiB image, at one of banks 0x10, 0x20 and 0x30.
+pub fn is_multicart(rom_data: &[u8]) -> bool {
+    if rom_data.len() != MULTICART_LEN {
+        return false;
+    }
+    (1..4).any(|game| {
+        let start = game * MULTICART_GAME_BANKS * ROM_BANK_SIZE;
+        rom_data[start + LOGO_START..start + LOGO_END] == NINTENDO_LOGO
+    })
+}
+
+/// MBC1 with up to 2 MiB of ROM and 32 KiB of RAM.
+///
+/// The 2-bit secondary register either extends the ROM bank number to 7 bits or selects the
+/// RAM bank. In mode 1 it also applies to 0x0000 - 0x3FFF and to RAM, which is how the large
+/// ROM and the large RAM boards reach their upper banks.
+///
+/// On MBC1M multicart boards the secondary register only extends the ROM bank to 6 bits and
+/// the top bit of the 5-bit register is not connected.
+#[derive(Debug, Clone)]
+pub struct Mbc1<S> {
+    rom_data: S,
+    rom_bank_mask: usize,
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    bank1: u8,  // Lower 5 bits of the ROM bank, written to 0x2000 - 0x3FFF
+    bank2: u8,  // Secondary register, written to 0x4000 - 0x5FFF
+    mode: bool, // Banking mode, written to 0x6000 - 0x7FFF
+    multicart: bool,
+}
+
+impl<S: AsRef<[u8]>> Mbc1<S> {
+    pub fn new(rom_data: S, ram_len: usize) -> Mbc1<S> {
+        Mbc1::with_wiring(rom_data, ram_len, false)
+    }
+
+    /// MBC1 wired as an MBC1M multicart board.
+    pub fn new_multicart(rom_data: S, ram_len: usize) -> Mbc1<S> {
+        Mbc1::with_wiring(rom_data, ram_len, true)
+    }
+
+    fn with_wiring(rom_data: S, ram_len: usize, multicart: bool) -> Mbc1<S> {
+        Mbc1 {
+            rom_bank_mask: rom_bank_mask(rom_data.as_ref().len()),
+            rom_data,
+            ram: vec![0; ram_len],
+            ram_enabled: false,
+            bank1: 1,
+            bank2: 0,
+            mode: false,
+            multicart,
+        }
+    }
+
+    pub fn into_rom_data(self) -> S {
+        self.rom_data
+    }
+
+    pub fn is_multicart(&self) -> bool {
+        self.multicart
+    }
+
+    // Number of bits of the ROM bank driven by the 5-bit register
+    fn bank1_bits(&self) -> u32 {
+        if self.multicart {
+            4
+        } else {
+            5
+        }
+    }
+
+    /// ROM bank mapped to 0x0000 - 0x3FFF.
+    pub fn rom_bank0(&self) -> usize {
+        let bank = if self.mode {
+            (self.bank2 as usize) << self.bank1_bits()
+        } else {
+            0
+        };
+        bank & self.rom_bank_mask
+    }
+
+    /// ROM bank mapped to 0x4000 - 0x7FFF.
+    pub fn rom_bank(&self) -> usize {
+        let bank1 = self.bank1 as usize & ((1 << self.bank1_bits()) - 1);
+        ((self.bank2 as usize) << self.bank1_bits() | bank1) & self.rom_bank_mask
+    }
+
+    /// RAM bank mapped to 0xA000 - 0xBFFF.
+    pub fn ram_bank(&self) -> usize {
+        if self.mode {
+            self.bank2 as usize
+        } else {
+            0
+        }
+    }
+
+    pub fn is_ram_enabled(&self) -> bool {
+        self.ram_enabled
+    }
+}
+
+impl<S: AsRef<[u8]>> Cartridge for Mbc1<S> {
+    fn read(&self, address: u16) -> u8 {
+        let offset = (address as usize) % ROM_BANK_SIZE;
+        match address {
+            0x0000..=0x3FFF => rom_byte(
+                self.rom_data.as_ref(),
+                self.rom_bank0() * ROM_BANK_SIZE + offset,
+            ),
+            0x4000..=0x7FFF => rom_byte(
+                self.rom_data.as_ref(),
+                self.rom_bank() * ROM_BANK_SIZE + offset,
+            ),
+            RAM_START..=RAM_END if self.ram_enabled && !self.ram.is_empty() => {
+                self.ram[ram_index(self.ram.len(), self.ram_bank(), address)]
+            }
+            _ => OPEN_BUS,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => {
+                // Bank 0 can not be selected here, the check ignores the ROM size
+                self.bank1 = match value & 0x1F {
+                    0 => 1,
+                    bank => bank,
+                }
+            }
+            0x4000..=0x5FFF => self.bank2 = value & 0x03,
+            0x6000..=0x7FFF => self.mode = value & 0x01 != 0,
+            RAM_START..=RAM_END if self.ram_enabled && !self.ram.is_empty() => {
+                let index = ram_index(self.ram.len(), self.ram_bank(), address);
+                self.ram[index] = value;
+            }
+            _ => {}
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mappers::numbered_rom;
+
+    // 1 MiB multicart with a logo at the start of each of its 4 games
+    fn multicart_rom() -> Vec<u8> {
+        let mut rom_data = numbered_rom(64);
+        for game in 0..4 {
+            let start = game * MULTICART_GAME_BANKS * ROM_BANK_SIZE + LOGO_START;
+            rom_data[start..start + NINTENDO_LOGO.len()].copy_from_slice(&NINTENDO_LOGO);
+        }
+        rom_data
+    }
+
+    #[test]
+    fn switch_rom_bank() {
+        let mut mbc = Mbc1::new(numbered_rom(32), 0);
+        assert_eq!(mbc.read(0x4000), 1);
+
+        mbc.write(0x2000, 0x05);
+        assert_eq!(mbc.read(0x4000), 5);
+        assert_eq!(mbc.read(0x0000), 0);
+    }
+
+    #[test]
+    fn bank_zero_selects_one() {
+        let mut mbc = Mbc1::new(numbered_rom(32), 0);
+        mbc.write(0x2000, 0x00);
+        assert_eq!(mbc.read(0x4000), 1);
+
+        // Only the lower 5 bits are compared, so 0x20 also selects bank 1
+        mbc.write(0x2000, 0x20);
+        assert_eq!(mbc.read(0x4000), 1);
+    }
+
+    #[test]
+    fn small_rom_masks_bank() {
+        let mut mbc = Mbc1::new(numbered_rom(8), 0);
+        mbc.write(0x2000, 0x09);
+        assert_eq!(mbc.read(0x4000), 1);
+
+        // 0x10 is not zero in 5 bits, but masks to bank 0 on a 128 KiB ROM
+        mbc.write(0x2000, 0x10);
+        assert_eq!(mbc.read(0x4000), 0);
+    }
+
+    #[test]
+    fn large_rom_banks() {
+        let mut mbc = Mbc1::new(numbered_rom(128), 0);
+        mbc.write(0x2000, 0x01);
+        mbc.write(0x4000, 0x02);
+        assert_eq!(mbc.read(0x4000), 0x41);
+        assert_eq!(mbc.read(0x0000), 0);
+
+        mbc.write(0x6000, 0x01);
+        assert_eq!(mbc.read(0x0000), 0x40);
+        assert_eq!(mbc.read(0x4000), 0x41);
+    }
+
+    #[test]
+    fn ram_enable() {
+        let mut mbc = Mbc1::new(numbered_rom(4), 0x2000);
+        mbc.write(0xA000, 0x12);
+        assert_eq!(mbc.read(0xA000), OPEN_BUS);
+        assert_eq!(mbc.ram()[0], 0);
+
+        mbc.write(0x0000, 0x0A);
+        mbc.write(0xA000, 0x12);
+        assert_eq!(mbc.read(0xA000), 0x12);
+
+        mbc.write(0x0000, 0x00);
+        assert_eq!(mbc.read(0xA000), OPEN_BUS);
+    }
+
+    #[test]
+    fn ram_banks() {
+        let mut mbc = Mbc1::new(numbered_rom(4), 0x8000);
+        mbc.write(0x0000, 0x0A);
+        mbc.write(0x4000, 0x02);
+        mbc.write(0xA000, 0x12);
+        assert_eq!(mbc.ram()[0], 0x12);
+
+        mbc.write(0x6000, 0x01);
+        mbc.write(0xA000, 0x34);
+        assert_eq!(mbc.ram()[0x4000], 0x34);
+        assert_eq!(mbc.read(0xA000), 0x34);
+
+        // The secondary register is masked off the ROM bank of a 4 bank ROM
+        assert_eq!(mbc.read(0x4000), 1);
+    }
+
+    #[test]
+    fn detect_multicart() {
+        assert!(is_multicart(&multicart_rom()));
+
+        let mut rom_data = numbered_rom(64);
+        rom_data[LOGO_START..LOGO_END].copy_from_slice(&NINTENDO_LOGO);
+        assert!(!is_multicart(&rom_data));
+
+        let mut rom_data = multicart_rom();
+        rom_data.truncate(0x80000);
+        assert!(!is_multicart(&rom_data));
+    }
+
+    #[test]
+    fn multicart_banks() {
+        let mut mbc = Mbc1::new_multicart(multicart_rom(), 0);
+        mbc.write(0x2000, 0x12);
+        mbc.write(0x4000, 0x01);
+        assert_eq!(mbc.read(0x4000), 0x12);
+
+        mbc.write(0x4000, 0x02);
+        assert_eq!(mbc.read(0x4000), 0x22);
+        assert_eq!(mbc.read(0x0000), 0x00);
+
+        // Mode 1 maps the first bank of the selected game to 0x0000 - 0x3FFF
+        mbc.write(0x6000, 0x01);
+        assert_eq!(mbc.read(0x0000), 0x20);
+        assert_eq!(mbc.read(0x0104), NINTENDO_LOGO[0]);
+    }
+
+    #[test]
+    fn multicart_bank_zero() {
+        let mut mbc = Mbc1::new_multicart(multicart_rom(), 0);
+        mbc.write(0x4000, 0x01);
+
+        // 0x10 passes the 5-bit zero check but bit 4 is not connected
+        mbc.write(0x2000, 0x10);
+        assert_eq!(mbc.read(0x4000), 0x10);
+        mbc.write(0x2000, 0x00);
+        assert_eq!(mbc.read(0x4000), 0x11);
+    }
+}
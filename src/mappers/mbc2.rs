@@ -0,0 +1,161 @@
+//! MBC2, which has 512 half-byte RAM cells built into the controller.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{rom_bank_mask, rom_byte, Cartridge, OPEN_BUS, RAM_END, RAM_START};
+use crate::error::SaveError;
+use crate::size::{MBC2_RAM_CELLS, ROM_BANK_SIZE};
+
+/// MBC2 with up to 256 KiB of ROM.
+///
+/// Address bit 8 decides which register a write to 0x0000 - 0x3FFF goes to. The RAM is
+/// mirrored across 0xA000 - 0xBFFF, only the lower nibble of each cell is stored and the upper
+/// nibble reads as 1s.
+#[derive(Debug, Clone)]
+pub struct Mbc2<S> {
+    rom_data: S,
+    rom_bank_mask: usize,
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    rom_bank: u8,
+}
+
+impl<S: AsRef<[u8]>> Mbc2<S> {
+    pub fn new(rom_data: S) -> Mbc2<S> {
+        Mbc2 {
+            rom_bank_mask: rom_bank_mask(rom_data.as_ref().len()),
+            rom_data,
+            ram: vec![0; MBC2_RAM_CELLS],
+            ram_enabled: false,
+            rom_bank: 1,
+        }
+    }
+
+    pub fn into_rom_data(self) -> S {
+        self.rom_data
+    }
+
+    /// ROM bank mapped to 0x4000 - 0x7FFF.
+    pub fn rom_bank(&self) -> usize {
+        self.rom_bank as usize & self.rom_bank_mask
+    }
+
+    pub fn is_ram_enabled(&self) -> bool {
+        self.ram_enabled
+    }
+}
+
+// RAM cell addressed by the lower 9 bits of `address`
+fn cell_index(address: u16) -> usize {
+    (address - RAM_START) as usize % MBC2_RAM_CELLS
+}
+
+impl<S: AsRef<[u8]>> Cartridge for Mbc2<S> {
+    fn read(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => rom_byte(self.rom_data.as_ref(), address as usize),
+            0x4000..=0x7FFF => rom_byte(
+                self.rom_data.as_ref(),
+                self.rom_bank() * ROM_BANK_SIZE + (address as usize - 0x4000),
+            ),
+            RAM_START..=RAM_END if self.ram_enabled => self.ram[cell_index(address)] | 0xF0,
+            _ => OPEN_BUS,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x3FFF if address & 0x0100 == 0 => self.ram_enabled = value & 0x0F == 0x0A,
+            0x0000..=0x3FFF => {
+                self.rom_bank = match value & 0x0F {
+                    0 => 1,
+                    bank => bank,
+                }
+            }
+            RAM_START..=RAM_END if self.ram_enabled => {
+                self.ram[cell_index(address)] = value & 0x0F;
+            }
+            _ => {}
+        }
+    }
+
+    /// The 512 RAM cells, one per byte. Only the lower nibble is used.
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    /// Loads the 512 cells, keeping only the lower nibble so saves with the upper nibbles set
+    /// or clear end up the same.
+    fn load_ram(&mut self, save: &[u8]) -> Result<(), SaveError> {
+        if save.len() != MBC2_RAM_CELLS {
+            return Err(SaveError::SizeMismatch {
+                len: save.len(),
+                expected: MBC2_RAM_CELLS,
+            });
+        }
+        for (cell, &byte) in self.ram.iter_mut().zip(save) {
+            *cell = byte & 0x0F;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mappers::numbered_rom;
+
+    #[test]
+    fn register_select() {
+        let mut mbc = Mbc2::new(numbered_rom(16));
+
+        // Address bit 8 clear writes the RAM enable register
+        mbc.write(0x0000, 0x05);
+        assert_eq!(mbc.read(0x4000), 1);
+        mbc.write(0x0000, 0x0A);
+        assert!(mbc.is_ram_enabled());
+
+        // Address bit 8 set writes the ROM bank register
+        mbc.write(0x0100, 0x05);
+        assert_eq!(mbc.read(0x4000), 5);
+        mbc.write(0x3FFF, 0x1F);
+        assert_eq!(mbc.read(0x4000), 15);
+        mbc.write(0x2100, 0x00);
+        assert_eq!(mbc.read(0x4000), 1);
+        assert!(mbc.is_ram_enabled());
+    }
+
+    #[test]
+    fn small_rom_masks_bank() {
+        let mut mbc = Mbc2::new(numbered_rom(4));
+        mbc.write(0x2100, 0x06);
+        assert_eq!(mbc.read(0x4000), 2);
+    }
+
+    #[test]
+    fn half_byte_ram() {
+        let mut mbc = Mbc2::new(numbered_rom(2));
+        mbc.write(0xA000, 0x05);
+        assert_eq!(mbc.read(0xA000), OPEN_BUS);
+
+        mbc.write(0x0000, 0x0A);
+        mbc.write(0xA000, 0x35);
+        assert_eq!(mbc.read(0xA000), 0xF5);
+        assert_eq!(mbc.ram()[0], 0x05);
+    }
+
+    #[test]
+    fn ram_mirrors() {
+        let mut mbc = Mbc2::new(numbered_rom(2));
+        mbc.write(0x0000, 0x0A);
+        mbc.write(0xA1FF, 0x0C);
+        assert_eq!(mbc.read(0xA3FF), 0xFC);
+        assert_eq!(mbc.read(0xBFFF), 0xFC);
+        assert_eq!(mbc.ram().len(), MBC2_RAM_CELLS);
+    }
+}
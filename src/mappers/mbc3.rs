@@ -0,0 +1,236 @@
+//! MBC3, with an optional real-time clock.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::rtc::{Clock, Rtc, RTC_DAY_HIGH, RTC_SECONDS};
+use super::{ram_index, rom_bank_mask, rom_byte, Cartridge, OPEN_BUS, RAM_END, RAM_START};
+use crate::size::ROM_BANK_SIZE;
+
+/// MBC3 with up to 2 MiB of ROM and 32 KiB of RAM.
+///
+/// Writing 0x00 - 0x07 to 0x4000 - 0x5FFF selects a RAM bank, 0x08 - 0x0C map one of the RTC
+/// registers to 0xA000 - 0xBFFF instead. The RTC is driven by the clock `C`.
+#[derive(Debug, Clone)]
+pub struct Mbc3<S, C> {
+    rom_data: S,
+    rom_bank_mask: usize,
+    ram: Vec<u8>,
+    ram_enabled: bool, // Also enables access to the RTC registers
+    rom_bank: u8,
+    ram_bank: u8, // RAM bank or RTC register
+    rtc: Option<Rtc>,
+    clock: C,
+}
+
+impl<S: AsRef<[u8]>, C: Clock> Mbc3<S, C> {
+    /// MBC3 without a real-time clock. `clock` is only kept so both kinds share a type.
+    pub fn new(rom_data: S, ram_len: usize, clock: C) -> Mbc3<S, C> {
+        Mbc3 {
+            rom_bank_mask: rom_bank_mask(rom_data.as_ref().len()),
+            rom_data,
+            ram: vec![0; ram_len],
+            ram_enabled: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            rtc: None,
+            clock,
+        }
+    }
+
+    /// MBC3 with a real-time clock started at the current time of `clock`.
+    pub fn with_rtc(rom_data: S, ram_len: usize, clock: C) -> Mbc3<S, C> {
+        let rtc = Rtc::new(clock.now());
+        Mbc3 {
+            rtc: Some(rtc),
+            ..Mbc3::new(rom_data, ram_len, clock)
+        }
+    }
+
+    pub fn into_rom_data(self) -> S {
+        self.rom_data
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// ROM bank mapped to 0x4000 - 0x7FFF.
+    pub fn rom_bank(&self) -> usize {
+        self.rom_bank as usize & self.rom_bank_mask
+    }
+
+    pub fn is_ram_enabled(&self) -> bool {
+        self.ram_enabled
+    }
+}
+
+impl<S: AsRef<[u8]>, C: Clock> Cartridge for Mbc3<S, C> {
+    fn read(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x3FFF => rom_byte(self.rom_data.as_ref(), address as usize),
+            0x4000..=0x7FFF => rom_byte(
+                self.rom_data.as_ref(),
+                self.rom_bank() * ROM_BANK_SIZE + (address as usize - 0x4000),
+            ),
+            RAM_START..=RAM_END if self.ram_enabled => match self.ram_bank {
+                0x00..=0x07 if !self.ram.is_empty() => {
+                    self.ram[ram_index(self.ram.len(), self.ram_bank as usize, address)]
+                }
+                RTC_SECONDS..=RTC_DAY_HIGH => self
+                    .rtc
+                    .as_ref()
+                    .and_then(|rtc| rtc.read(self.ram_bank))
+                    .unwrap_or(OPEN_BUS),
+                _ => OPEN_BUS,
+            },
+            _ => OPEN_BUS,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => {
+                self.rom_bank = match value & 0x7F {
+                    0 => 1,
+                    bank => bank,
+                }
+            }
+            0x4000..=0x5FFF => self.ram_bank = value,
+            0x6000..=0x7FFF => {
+                let now = self.clock.now();
+                if let Some(rtc) = &mut self.rtc {
+                    rtc.write_latch(value, now);
+                }
+            }
+            RAM_START..=RAM_END if self.ram_enabled => match self.ram_bank {
+                0x00..=0x07 if !self.ram.is_empty() => {
+                    let index = ram_index(self.ram.len(), self.ram_bank as usize, address);
+                    self.ram[index] = value;
+                }
+                RTC_SECONDS..=RTC_DAY_HIGH => {
+                    let now = self.clock.now();
+                    if let Some(rtc) = &mut self.rtc {
+                        rtc.write(self.ram_bank, value, now);
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn rtc(&self) -> Option<&Rtc> {
+        self.rtc.as_ref()
+    }
+
+    fn rtc_mut(&mut self) -> Option<&mut Rtc> {
+        self.rtc.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mappers::numbered_rom;
+    use crate::mappers::rtc::{ManualClock, RTC_DAY_LOW, RTC_HOURS, RTC_MINUTES};
+
+    fn latch<C: Cartridge>(mbc: &mut C) {
+        mbc.write(0x6000, 0x00);
+        mbc.write(0x6000, 0x01);
+    }
+
+    fn read_rtc<C: Cartridge>(mbc: &mut C, register: u8) -> u8 {
+        mbc.write(0x4000, register);
+        mbc.read(0xA000)
+    }
+
+    #[test]
+    fn switch_rom_bank() {
+        let mut mbc = Mbc3::new(numbered_rom(128), 0, ManualClock::default());
+        mbc.write(0x2000, 0x45);
+        assert_eq!(mbc.read(0x4000), 0x45);
+        assert_eq!(mbc.read(0x0000), 0x00);
+
+        mbc.write(0x2000, 0x80);
+        assert_eq!(mbc.read(0x4000), 0x01);
+    }
+
+    #[test]
+    fn ram_banks() {
+        let mut mbc = Mbc3::new(numbered_rom(4), 0x8000, ManualClock::default());
+        mbc.write(0x0000, 0x0A);
+        mbc.write(0x4000, 0x03);
+        mbc.write(0xA000, 0x12);
+        assert_eq!(mbc.ram()[0x6000], 0x12);
+        assert_eq!(mbc.read(0xA000), 0x12);
+
+        mbc.write(0x0000, 0x00);
+        assert_eq!(mbc.read(0xA000), OPEN_BUS);
+    }
+
+    #[test]
+    fn no_rtc() {
+        let mut mbc = Mbc3::new(numbered_rom(4), 0x2000, ManualClock::default());
+        mbc.write(0x0000, 0x0A);
+        assert_eq!(read_rtc(&mut mbc, RTC_SECONDS), OPEN_BUS);
+        assert!(mbc.rtc().is_none());
+    }
+
+    #[test]
+    fn rtc_counts_after_latch() {
+        let clock = ManualClock::new(1000);
+        let mut mbc = Mbc3::with_rtc(numbered_rom(4), 0x2000, &clock);
+        mbc.write(0x0000, 0x0A);
+
+        clock.advance(2 * 86400 + 3 * 3600 + 4 * 60 + 5);
+        assert_eq!(read_rtc(&mut mbc, RTC_SECONDS), 0);
+
+        latch(&mut mbc);
+        assert_eq!(read_rtc(&mut mbc, RTC_SECONDS), 5);
+        assert_eq!(read_rtc(&mut mbc, RTC_MINUTES), 4);
+        assert_eq!(read_rtc(&mut mbc, RTC_HOURS), 3);
+        assert_eq!(read_rtc(&mut mbc, RTC_DAY_LOW), 2);
+        assert_eq!(read_rtc(&mut mbc, RTC_DAY_HIGH), 0);
+    }
+
+    #[test]
+    fn rtc_write_and_halt() {
+        let clock = ManualClock::new(0);
+        let mut mbc = Mbc3::with_rtc(numbered_rom(4), 0, &clock);
+        mbc.write(0x0000, 0x0A);
+
+        mbc.write(0x4000, RTC_DAY_HIGH);
+        mbc.write(0xA000, 0x40);
+        mbc.write(0x4000, RTC_HOURS);
+        mbc.write(0xA000, 0x17);
+        assert_eq!(mbc.read(0xA000), 0x17);
+
+        clock.advance(3600);
+        latch(&mut mbc);
+        assert_eq!(read_rtc(&mut mbc, RTC_HOURS), 0x17);
+
+        mbc.write(0x4000, RTC_DAY_HIGH);
+        mbc.write(0xA000, 0x00);
+        clock.advance(3600);
+        latch(&mut mbc);
+        assert_eq!(read_rtc(&mut mbc, RTC_HOURS), 0x00);
+        assert_eq!(read_rtc(&mut mbc, RTC_DAY_LOW), 0x01);
+    }
+
+    #[test]
+    fn rtc_disabled() {
+        let clock = ManualClock::new(0);
+        let mut mbc = Mbc3::with_rtc(numbered_rom(4), 0, &clock);
+        assert_eq!(read_rtc(&mut mbc, RTC_SECONDS), OPEN_BUS);
+    }
+}
@@ -0,0 +1,299 @@
+//! Memory bank controller implementations.
+//!
+//! Every mapper implements [`Cartridge`], the side of the cartridge slot an emulator core sees:
+//! ROM at 0x0000 - 0x7FFF and external RAM at 0xA000 - 0xBFFF. Writes to the ROM range go to
+//! the mapper registers.
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+#[cfg(feature = "alloc")]
+use rtc::Clock;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use rtc::ManualClock;
+use rtc::Rtc;
+#[cfg(feature = "std")]
+use rtc::SystemClock;
+
+#[cfg(feature = "alloc")]
+use crate::cartridge::{CartridgeType, Mapper};
+#[cfg(feature = "alloc")]
+use crate::error::CartridgeError;
+use crate::error::SaveError;
+#[cfg(feature = "alloc")]
+use crate::header::DMG;
+#[cfg(feature = "alloc")]
+use crate::size::{RamSize, RAM_BANK_SIZE, ROM_BANK_SIZE};
+
+#[cfg(feature = "alloc")]
+pub mod mbc1;
+#[cfg(feature = "alloc")]
+pub mod mbc2;
+#[cfg(feature = "alloc")]
+pub mod mbc3;
+#[cfg(feature = "alloc")]
+pub mod rom_only;
+pub mod rtc;
+
+#[cfg(feature = "alloc")]
+pub use mbc1::Mbc1;
+#[cfg(feature = "alloc")]
+pub use mbc2::Mbc2;
+#[cfg(feature = "alloc")]
+pub use mbc3::Mbc3;
+#[cfg(feature = "alloc")]
+pub use rom_only::RomOnly;
+
+pub const ROM_START: u16 = 0x0000;
+pub const ROM_END: u16 = 0x7FFF;
+pub const RAM_START: u16 = 0xA000;
+pub const RAM_END: u16 = 0xBFFF;
+
+/// Value read from addresses nothing drives, such as disabled RAM.
+pub const OPEN_BUS: u8 = 0xFF;
+
+/// A cartridge as seen from the memory bus.
+pub trait Cartridge {
+    /// Reads the byte at `address`. Addresses outside the cartridge ranges read as [`OPEN_BUS`].
+    fn read(&self, address: u16) -> u8;
+
+    /// Writes `value` to `address`. Writes outside the cartridge ranges are ignored.
+    fn write(&mut self, address: u16, value: u8);
+
+    /// External RAM in the layout it is saved to disk, empty if the cartridge has none.
+    fn ram(&self) -> &[u8];
+
+    fn ram_mut(&mut self) -> &mut [u8];
+
+    /// Restores the RAM from `save`, which must be exactly as long as the RAM.
+    fn load_ram(&mut self, save: &[u8]) -> Result<(), SaveError> {
+        let ram = self.ram_mut();
+        if ram.len() != save.len() {
+            return Err(SaveError::SizeMismatch {
+                len: save.len(),
+                expected: ram.len(),
+            });
+        }
+        ram.copy_from_slice(save);
+        Ok(())
+    }
+
+    /// Real-time clock, for the cartridges that have one.
+    fn rtc(&self) -> Option<&Rtc> {
+        None
+    }
+
+    fn rtc_mut(&mut self) -> Option<&mut Rtc> {
+        None
+    }
+}
+
+/// Creates the mapper for `cartridge_type` over the ROM image `rom_data`.
+///
+/// MBC1 images that look like multicarts, see [`mbc1::is_multicart`], get the MBC1M wiring.
+///
+/// `ram_size` decides how much external RAM is allocated, an unknown size allocates none. MBC2
+/// always has its 512 built-in cells.
+///
+/// The real-time clock of MBC3 cartridges follows the system time. Without the `std` feature
+/// there is no system time and the clock stands still, use [`with_clock`] to drive it.
+#[cfg(feature = "alloc")]
+pub fn new<'a, S: AsRef<[u8]> + 'a>(
+    rom_data: S,
+    cartridge_type: CartridgeType,
+    ram_size: RamSize,
+) -> Result<Box<dyn Cartridge + 'a>, CartridgeError> {
+    #[cfg(feature = "std")]
+    let clock = SystemClock;
+    #[cfg(not(feature = "std"))]
+    let clock = ManualClock::default();
+    with_clock(rom_data, cartridge_type, ram_size, clock)
+}
+
+/// Same as [`new`], with the real-time clock of MBC3 cartridges driven by `clock`.
+#[cfg(feature = "alloc")]
+pub fn with_clock<'a, S: AsRef<[u8]> + 'a, C: Clock + 'a>(
+    rom_data: S,
+    cartridge_type: CartridgeType,
+    ram_size: RamSize,
+    clock: C,
+) -> Result<Box<dyn Cartridge + 'a>, CartridgeError> {
+    let ram_len = ram_size.bytes().unwrap_or(0);
+    match cartridge_type.mapper() {
+        Mapper::None => Ok(Box::new(RomOnly::new(rom_data, ram_len))),
+        Mapper::Mbc1 if mbc1::is_multicart(rom_data.as_ref()) => {
+            Ok(Box::new(Mbc1::new_multicart(rom_data, ram_len)))
+        }
+        Mapper::Mbc1 => Ok(Box::new(Mbc1::new(rom_data, ram_len))),
+        Mapper::Mbc2 => Ok(Box::new(Mbc2::new(rom_data))),
+        Mapper::Mbc3 if cartridge_type.has_rtc() => {
+            Ok(Box::new(Mbc3::with_rtc(rom_data, ram_len, clock)))
+        }
+        Mapper::Mbc3 => Ok(Box::new(Mbc3::new(rom_data, ram_len, clock))),
+        _ => Err(CartridgeError::UnsupportedMapper { cartridge_type }),
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: AsRef<[u8]>> DMG<S> {
+    /// Whether the ROM is an MBC1 cartridge that looks like an MBC1M multicart.
+    pub fn is_mbc1_multicart(&self) -> bool {
+        self.cartridge_type().mapper() == Mapper::Mbc1 && mbc1::is_multicart(self.get_rom_data())
+    }
+
+    /// Turns the loaded ROM into the mapper declared by its header.
+    pub fn into_cartridge<'a>(self) -> Result<Box<dyn Cartridge + 'a>, CartridgeError>
+    where
+        S: 'a,
+    {
+        let cartridge_type = self.cartridge_type();
+        let ram_size = self.ram_size();
+        new(self.into_rom_data(), cartridge_type, ram_size)
+    }
+
+    /// Same as [`DMG::into_cartridge`], with the real-time clock driven by `clock`.
+    pub fn into_cartridge_with_clock<'a, C: Clock + 'a>(
+        self,
+        clock: C,
+    ) -> Result<Box<dyn Cartridge + 'a>, CartridgeError>
+    where
+        S: 'a,
+    {
+        let cartridge_type = self.cartridge_type();
+        let ram_size = self.ram_size();
+        with_clock(self.into_rom_data(), cartridge_type, ram_size, clock)
+    }
+}
+
+// Byte at `offset` of the ROM image, images shorter than the declared size read as open bus
+#[cfg(feature = "alloc")]
+pub(crate) fn rom_byte(rom_data: &[u8], offset: usize) -> u8 {
+    rom_data.get(offset).copied().unwrap_or(OPEN_BUS)
+}
+
+// Mask applied to ROM bank numbers, the image is treated as rounded up to a power of two banks
+#[cfg(feature = "alloc")]
+pub(crate) fn rom_bank_mask(len: usize) -> usize {
+    len.div_ceil(ROM_BANK_SIZE).max(2).next_power_of_two() - 1
+}
+
+// Index into external RAM for `address` in the given bank, RAM smaller than the bank is mirrored
+#[cfg(feature = "alloc")]
+pub(crate) fn ram_index(ram_len: usize, bank: usize, address: u16) -> usize {
+    (bank * RAM_BANK_SIZE + (address - RAM_START) as usize) % ram_len
+}
+
+// ROM whose banks start with their own bank number
+#[cfg(all(test, feature = "alloc"))]
+pub(crate) fn numbered_rom(banks: usize) -> alloc::vec::Vec<u8> {
+    let mut rom_data = vec![0; banks * ROM_BANK_SIZE];
+    for bank in 0..banks {
+        rom_data[bank * ROM_BANK_SIZE] = bank as u8;
+    }
+    rom_data
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::editor::HeaderEditor;
+    use crate::logo::{LOGO_END, LOGO_START};
+    use crate::mappers::rtc::ManualClock;
+    use crate::size::RomSize;
+    use std::fs;
+
+    // Test header on a ROM of `banks` numbered banks
+    fn load_rom(cartridge_type: CartridgeType, banks: usize) -> DMG {
+        let header = fs::read("test_roms/header_only_test.gb").unwrap();
+        let mut rom_data = numbered_rom(banks);
+        rom_data[..header.len()].copy_from_slice(&header);
+
+        let mut editor = HeaderEditor::new(rom_data).unwrap();
+        editor
+            .set_cartridge_type(cartridge_type)
+            .set_rom_size(RomSize::for_len(banks * ROM_BANK_SIZE).unwrap())
+            .set_ram_size(RamSize::None);
+        editor.finish().unwrap()
+    }
+
+    #[test]
+    fn into_cartridge_rom_only() {
+        let cartridge = load_rom(CartridgeType::RomOnly, 2)
+            .into_cartridge()
+            .unwrap();
+        assert_eq!(cartridge.read(0x0134), b'G');
+        assert_eq!(cartridge.read(RAM_START), OPEN_BUS);
+        assert!(cartridge.ram().is_empty());
+    }
+
+    #[test]
+    fn into_cartridge_mbc2() {
+        let mut cartridge = load_rom(CartridgeType::Mbc2Battery, 2)
+            .into_cartridge()
+            .unwrap();
+        cartridge.write(0x0000, 0x0A);
+        cartridge.write(0xA000, 0x03);
+        assert_eq!(cartridge.read(0xA200), 0xF3);
+        assert_eq!(cartridge.ram().len(), 512);
+    }
+
+    #[test]
+    fn into_cartridge_mbc3() {
+        let clock = ManualClock::new(0);
+        let rom = load_rom(CartridgeType::Mbc3TimerBattery, 2);
+        let mut cartridge = rom.into_cartridge_with_clock(&clock).unwrap();
+        clock.advance(30);
+
+        cartridge.write(0x0000, 0x0A);
+        cartridge.write(0x6000, 0x00);
+        cartridge.write(0x6000, 0x01);
+        cartridge.write(0x4000, 0x08);
+        assert_eq!(cartridge.read(0xA000), 30);
+
+        let cartridge = load_rom(CartridgeType::Mbc3Ram, 2)
+            .into_cartridge()
+            .unwrap();
+        assert!(cartridge.rtc().is_none());
+    }
+
+    #[test]
+    fn into_cartridge_unsupported() {
+        let error = load_rom(CartridgeType::HuC3, 2).into_cartridge().err();
+        assert_eq!(
+            error,
+            Some(CartridgeError::UnsupportedMapper {
+                cartridge_type: CartridgeType::HuC3
+            })
+        );
+    }
+
+    #[test]
+    fn into_cartridge_mbc1() {
+        let mut cartridge = load_rom(CartridgeType::Mbc1, 8).into_cartridge().unwrap();
+        assert_eq!(cartridge.read(0x4000), 1);
+        cartridge.write(0x2000, 0x02);
+        assert_eq!(cartridge.read(0x4000), 2);
+        cartridge.write(0x2000, 0x07);
+        assert_eq!(cartridge.read(0x4000), 7);
+    }
+
+    #[test]
+    fn detect_mbc1_multicart() {
+        // A second game with its own logo at bank 0x10
+        let mut rom_data = load_rom(CartridgeType::Mbc1, 64).into_rom_data();
+        rom_data.copy_within(LOGO_START..LOGO_END, 0x40000 + LOGO_START);
+
+        let rom = DMG::new(rom_data.clone()).unwrap();
+        assert!(rom.is_mbc1_multicart());
+
+        rom_data[0x147] = 0x19;
+        assert!(!DMG::new(rom_data).unwrap().is_mbc1_multicart());
+        assert!(!load_rom(CartridgeType::Mbc1, 64).is_mbc1_multicart());
+    }
+
+    #[test]
+    fn new_allocates_ram() {
+        let cartridge = new(vec![0; 0x8000], CartridgeType::RomRam, RamSize::Kib8).unwrap();
+        assert_eq!(cartridge.ram().len(), 0x2000);
+    }
+}
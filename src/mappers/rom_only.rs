@@ -0,0 +1,94 @@
+//! Cartridges without a memory bank controller.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{ram_index, rom_byte, Cartridge, OPEN_BUS, RAM_END, RAM_START, ROM_END};
+
+/// 32 KiB of ROM wired straight to 0x0000 - 0x7FFF, with up to 8 KiB of optional RAM.
+#[derive(Debug, Clone)]
+pub struct RomOnly<S> {
+    rom_data: S,
+    ram: Vec<u8>,
+}
+
+impl<S: AsRef<[u8]>> RomOnly<S> {
+    pub fn new(rom_data: S, ram_len: usize) -> RomOnly<S> {
+        RomOnly {
+            rom_data,
+            ram: vec![0; ram_len],
+        }
+    }
+
+    pub fn into_rom_data(self) -> S {
+        self.rom_data
+    }
+}
+
+impl<S: AsRef<[u8]>> Cartridge for RomOnly<S> {
+    fn read(&self, address: u16) -> u8 {
+        match address {
+            0..=ROM_END => rom_byte(self.rom_data.as_ref(), address as usize),
+            RAM_START..=RAM_END if !self.ram.is_empty() => {
+                self.ram[ram_index(self.ram.len(), 0, address)]
+            }
+            _ => OPEN_BUS,
+        }
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        if let RAM_START..=RAM_END = address {
+            if !self.ram.is_empty() {
+                let index = ram_index(self.ram.len(), 0, address);
+                self.ram[index] = value;
+            }
+        }
+    }
+
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_rom() {
+        let mut rom_data = vec![0; 0x8000];
+        rom_data[0x0000] = 0x31;
+        rom_data[0x7FFF] = 0x42;
+
+        let mut cartridge = RomOnly::new(rom_data, 0);
+        cartridge.write(0x2000, 0x01);
+        assert_eq!(cartridge.read(0x0000), 0x31);
+        assert_eq!(cartridge.read(0x7FFF), 0x42);
+        assert_eq!(cartridge.read(0x8000), OPEN_BUS);
+    }
+
+    #[test]
+    fn read_short_rom() {
+        let cartridge = RomOnly::new(vec![0; 0x150], 0);
+        assert_eq!(cartridge.read(0x4000), OPEN_BUS);
+    }
+
+    #[test]
+    fn ram_mirrors() {
+        let mut cartridge = RomOnly::new(vec![0; 0x8000], 0x800);
+        cartridge.write(0xA001, 0x12);
+        assert_eq!(cartridge.read(0xA801), 0x12);
+        assert_eq!(cartridge.ram()[1], 0x12);
+    }
+
+    #[test]
+    fn no_ram() {
+        let mut cartridge = RomOnly::new(vec![0; 0x8000], 0);
+        cartridge.write(0xA000, 0x12);
+        assert_eq!(cartridge.read(0xA000), OPEN_BUS);
+    }
+}
@@ -0,0 +1,328 @@
+//! The MBC3 real-time clock and the clock sources driving it.
+
+use core::cell::Cell;
+
+/// Registers selected by writing 0x08 - 0x0C to 0x4000 - 0x5FFF.
+pub const RTC_SECONDS: u8 = 0x08;
+pub const RTC_MINUTES: u8 = 0x09;
+pub const RTC_HOURS: u8 = 0x0A;
+pub const RTC_DAY_LOW: u8 = 0x0B;
+pub const RTC_DAY_HIGH: u8 = 0x0C;
+
+const DAY_HIGH_BIT: u8 = 0x01;
+const HALT_BIT: u8 = 0x40;
+const DAY_CARRY_BIT: u8 = 0x80;
+
+/// Number of days the 9-bit day counter can hold before it sets the carry bit.
+pub const RTC_DAYS: u64 = 512;
+
+/// Source of the current time for a real-time clock.
+pub trait Clock {
+    /// Current time in seconds. `SystemClock` counts from the Unix epoch, which is what RTC
+    /// saves expect.
+    fn now(&self) -> u64;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+/// Wall clock time.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    }
+}
+
+/// Clock that only moves when told to, for tests and deterministic replays.
+///
+/// Pass a reference to the mapper to keep advancing the clock after handing it over.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    seconds: Cell<u64>,
+}
+
+impl ManualClock {
+    pub fn new(seconds: u64) -> ManualClock {
+        ManualClock {
+            seconds: Cell::new(seconds),
+        }
+    }
+
+    pub fn set(&self, seconds: u64) {
+        self.seconds.set(seconds);
+    }
+
+    pub fn advance(&self, seconds: u64) {
+        self.seconds.set(self.seconds.get() + seconds);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.seconds.get()
+    }
+}
+
+/// Time kept by the RTC, as exposed through its registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RtcRegisters {
+    pub seconds: u8,
+    pub minutes: u8,
+    pub hours: u8,
+    pub days: u16, // 9-bit day counter, split across DL and bit 0 of DH
+    pub halted: bool,
+    pub day_carry: bool,
+}
+
+impl RtcRegisters {
+    /// Reads one of the registers 0x08 - 0x0C, unused bits read as 0.
+    pub fn read(&self, register: u8) -> Option<u8> {
+        match register {
+            RTC_SECONDS => Some(self.seconds & 0x3F),
+            RTC_MINUTES => Some(self.minutes & 0x3F),
+            RTC_HOURS => Some(self.hours & 0x1F),
+            RTC_DAY_LOW => Some(self.days as u8),
+            RTC_DAY_HIGH => {
+                let mut value = (self.days >> 8) as u8 & DAY_HIGH_BIT;
+                if self.halted {
+                    value |= HALT_BIT;
+                }
+                if self.day_carry {
+                    value |= DAY_CARRY_BIT;
+                }
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Writes one of the registers 0x08 - 0x0C, other registers are ignored.
+    pub fn write(&mut self, register: u8, value: u8) {
+        match register {
+            RTC_SECONDS => self.seconds = value & 0x3F,
+            RTC_MINUTES => self.minutes = value & 0x3F,
+            RTC_HOURS => self.hours = value & 0x1F,
+            RTC_DAY_LOW => self.days = (self.days & 0x100) | value as u16,
+            RTC_DAY_HIGH => {
+                self.days = (self.days & 0xFF) | ((value & DAY_HIGH_BIT) as u16) << 8;
+                self.halted = value & HALT_BIT != 0;
+                self.day_carry = value & DAY_CARRY_BIT != 0;
+            }
+            _ => {}
+        }
+    }
+
+    /// Counts `seconds` forward, setting the carry bit when the day counter overflows.
+    ///
+    /// Like the real chip, a register holding an out of range value written by the game
+    /// counts up to the limit of its bits and wraps to 0 without carrying into the next one.
+    pub fn advance(&mut self, seconds: u64) {
+        let minutes = count(&mut self.seconds, seconds, 60, 64);
+        let hours = count(&mut self.minutes, minutes, 60, 64);
+        let days = self.days as u64 + count(&mut self.hours, hours, 24, 32);
+
+        self.days = (days % RTC_DAYS) as u16;
+        if days >= RTC_DAYS {
+            self.day_carry = true;
+        }
+    }
+}
+
+// Counts a register `ticks` times forward and returns how often it carried into the next one.
+// A value of `modulus` or more counts up to `limit` and wraps to 0 without carrying.
+fn count(register: &mut u8, ticks: u64, modulus: u64, limit: u64) -> u64 {
+    let mut value = *register as u64 % limit;
+    let mut ticks = ticks;
+    if value >= modulus {
+        if ticks < limit - value {
+            *register = (value + ticks) as u8;
+            return 0;
+        }
+        ticks -= limit - value;
+        value = 0;
+    }
+
+    let total = value + ticks;
+    *register = (total % modulus) as u8;
+    total / modulus
+}
+
+/// Real-time clock of an MBC3 cartridge.
+///
+/// The registers are brought up to date lazily, from the time passed in by the mapper, and
+/// only the latched copy is visible on the memory bus.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Rtc {
+    registers: RtcRegisters,
+    latched: RtcRegisters,
+    timestamp: u64,    // Time the registers were last brought up to date
+    latch_armed: bool, // 0x00 was written to 0x6000 - 0x7FFF, a 0x01 latches the registers
+}
+
+impl Rtc {
+    /// RTC started at `now` with all registers cleared.
+    pub fn new(now: u64) -> Rtc {
+        Rtc {
+            timestamp: now,
+            ..Rtc::default()
+        }
+    }
+
+    /// Running registers, as of [`Rtc::timestamp`].
+    pub fn registers(&self) -> RtcRegisters {
+        self.registers
+    }
+
+    /// Registers copied by the last latch, which are the ones the game reads.
+    pub fn latched(&self) -> RtcRegisters {
+        self.latched
+    }
+
+    /// Time the running registers were last brought up to date.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Restores the registers as they were at `timestamp`, the time since is counted on the
+    /// next update.
+    pub fn restore(&mut self, registers: RtcRegisters, latched: RtcRegisters, timestamp: u64) {
+        self.registers = registers;
+        self.latched = latched;
+        self.timestamp = timestamp;
+    }
+
+    /// Counts the time since the last update, unless the clock is halted.
+    pub fn update(&mut self, now: u64) {
+        if !self.registers.halted {
+            self.registers.advance(now.saturating_sub(self.timestamp));
+        }
+        self.timestamp = now;
+    }
+
+    /// Handles a write to 0x6000 - 0x7FFF, where 0x00 followed by 0x01 latches the registers.
+    pub fn write_latch(&mut self, value: u8, now: u64) {
+        if self.latch_armed && value == 0x01 {
+            self.update(now);
+            self.latched = self.registers;
+        }
+        self.latch_armed = value == 0x00;
+    }
+
+    pub fn read(&self, register: u8) -> Option<u8> {
+        self.latched.read(register)
+    }
+
+    /// Writes a running register, and its latched copy so the game reads back what it wrote.
+    pub fn write(&mut self, register: u8, value: u8, now: u64) {
+        self.update(now);
+        self.registers.write(register, value);
+        self.latched.write(register, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_carries() {
+        let mut registers = RtcRegisters {
+            seconds: 59,
+            minutes: 59,
+            hours: 23,
+            days: 511,
+            ..RtcRegisters::default()
+        };
+        registers.advance(1);
+        assert_eq!(
+            registers,
+            RtcRegisters {
+                day_carry: true,
+                ..RtcRegisters::default()
+            }
+        );
+    }
+
+    #[test]
+    fn advance_wraps_invalid_values() {
+        let mut registers = RtcRegisters {
+            seconds: 61,
+            minutes: 5,
+            ..RtcRegisters::default()
+        };
+        registers.advance(2);
+        assert_eq!((registers.seconds, registers.minutes), (63, 5));
+        registers.advance(1);
+        assert_eq!((registers.seconds, registers.minutes), (0, 5));
+        registers.advance(60);
+        assert_eq!((registers.seconds, registers.minutes), (0, 6));
+
+        let mut registers = RtcRegisters {
+            minutes: 62,
+            hours: 25,
+            ..RtcRegisters::default()
+        };
+        registers.advance(2 * 60);
+        assert_eq!((registers.minutes, registers.hours), (0, 25));
+        registers.advance(7 * 3600);
+        assert_eq!((registers.hours, registers.days), (0, 0));
+        registers.advance(24 * 3600);
+        assert_eq!((registers.hours, registers.days), (0, 1));
+    }
+
+    #[test]
+    fn day_high_register() {
+        let mut registers = RtcRegisters::default();
+        registers.write(RTC_DAY_HIGH, 0xC1);
+        registers.write(RTC_DAY_LOW, 0x23);
+        assert_eq!(registers.days, 0x123);
+        assert!(registers.halted);
+        assert!(registers.day_carry);
+        assert_eq!(registers.read(RTC_DAY_HIGH), Some(0xC1));
+    }
+
+    #[test]
+    fn latch_sequence() {
+        let mut rtc = Rtc::new(0);
+        rtc.write_latch(0x01, 10);
+        assert_eq!(rtc.read(RTC_SECONDS), Some(0));
+
+        rtc.write_latch(0x00, 10);
+        rtc.write_latch(0x01, 10);
+        assert_eq!(rtc.read(RTC_SECONDS), Some(10));
+
+        // Another 0x01 without a 0x00 first does not latch again
+        rtc.write_latch(0x01, 20);
+        assert_eq!(rtc.read(RTC_SECONDS), Some(10));
+    }
+
+    #[test]
+    fn halt_stops_clock() {
+        let mut rtc = Rtc::new(0);
+        rtc.write(RTC_DAY_HIGH, HALT_BIT, 5);
+        rtc.update(100);
+        assert_eq!(rtc.registers().seconds, 5);
+
+        rtc.write(RTC_DAY_HIGH, 0x00, 200);
+        rtc.update(203);
+        assert_eq!(rtc.registers().seconds, 8);
+    }
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new(100);
+        let by_reference = &clock;
+        clock.advance(5);
+        assert_eq!(by_reference.now(), 105);
+    }
+}
@@ -0,0 +1,92 @@
+//! Memory-mapped ROM images, enabled by the `mmap` feature.
+//!
+//! A [`MappedDMG`] offers the same header and bank accessors as a [`DMG`] loaded into a
+//! `Vec<u8>`, but pages the image in from the file on demand instead of copying it.
+
+use std::fs::File;
+use std::path::Path;
+
+pub use memmap2::Mmap;
+
+use crate::error::ReadError;
+use crate::header::{LoadOptions, DMG};
+
+/// A [`DMG`] backed by a read-only memory map.
+pub type MappedDMG = DMG<Mmap>;
+
+impl DMG<Mmap> {
+    /// Maps the ROM at `path` into memory and parses its header.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by this or another process while the
+    /// map is alive, see [`Mmap::map`].
+    pub unsafe fn map_path<P: AsRef<Path>>(path: P) -> Result<MappedDMG, ReadError> {
+        DMG::map_path_with_options(path, LoadOptions::default())
+    }
+
+    /// # Safety
+    ///
+    /// See [`DMG::map_path`].
+    pub unsafe fn map_path_with_options<P: AsRef<Path>>(
+        path: P,
+        options: LoadOptions,
+    ) -> Result<MappedDMG, ReadError> {
+        let file = File::open(path)?;
+        let mmap = Mmap::map(&file)?;
+        Ok(DMG::with_options(mmap, options)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checksum;
+    use std::{env, fs, process};
+
+    const FIXTURE: &str = "test_roms/header_only_test.gb";
+
+    #[test]
+    fn map_path() {
+        let mapped = unsafe { DMG::map_path(FIXTURE) }.unwrap();
+        let loaded = DMG::from_path(FIXTURE).unwrap();
+
+        assert_eq!(mapped.get_title(), loaded.get_title());
+        assert_eq!(mapped.get_rom_data(), loaded.get_rom_data());
+        assert_eq!(
+            mapped.computed_global_checksum(),
+            loaded.computed_global_checksum()
+        );
+    }
+
+    #[test]
+    fn map_path_banks() {
+        let mut rom_data = fs::read(FIXTURE).unwrap();
+        rom_data.resize(0x20000, 0xFF);
+        rom_data[0x1C000] = 0x42;
+        checksum::fix_global_checksum(&mut rom_data).unwrap();
+
+        let path = env::temp_dir().join(format!("gbloader-mmap-{}.gb", process::id()));
+        fs::write(&path, &rom_data).unwrap();
+
+        let mapped = unsafe { DMG::map_path(&path) }.unwrap();
+        assert_eq!(mapped.rom_bank_count(), 8);
+        assert_eq!(mapped.rom_bank(7).unwrap()[0], 0x42);
+        assert!(mapped.verify_rom_size().is_ok());
+        assert!(mapped.verify_global_checksum().is_ok());
+
+        drop(mapped);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn map_path_too_short() {
+        let path = env::temp_dir().join(format!("gbloader-mmap-short-{}.gb", process::id()));
+        fs::write(&path, [0; 0x20]).unwrap();
+
+        let result = unsafe { DMG::map_path(&path) };
+        assert!(matches!(result, Err(ReadError::Load(_))));
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+//! Destination code at 0x014A and a best-guess release region.
+
+use core::fmt;
+
+use crate::licensee;
+
+/// Destination code read from 0x014A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Destination {
+    Japan,       // 0x00
+    Overseas,    // 0x01
+    Unknown(u8), // Any other value
+}
+
+impl Destination {
+    pub fn from_code(code: u8) -> Destination {
+        match code {
+            0x00 => Destination::Japan,
+            0x01 => Destination::Overseas,
+            code => Destination::Unknown(code),
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self {
+            Destination::Japan => 0x00,
+            Destination::Overseas => 0x01,
+            Destination::Unknown(code) => code,
+        }
+    }
+}
+
+impl From<u8> for Destination {
+    fn from(code: u8) -> Destination {
+        Destination::from_code(code)
+    }
+}
+
+impl fmt::Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Destination::Japan => f.write_str("Japan"),
+            Destination::Overseas => f.write_str("Overseas"),
+            Destination::Unknown(code) => write!(f, "Unknown (0x{:02X})", code),
+        }
+    }
+}
+
+/// Release region of a ROM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Region {
+    Japan,
+    Usa,
+    Europe,
+    Germany,
+    France,
+    Italy,
+    Spain,
+    Netherlands,
+    Australia,
+    Korea,
+    // Outside of Japan, without more detail
+    Overseas,
+    Unknown,
+}
+
+impl Region {
+    /// Region from the last character of a manufacturer code, e.g. the 'E' of "AAXE".
+    pub fn from_manufacturer_code(code: &str) -> Option<Region> {
+        match code.chars().last()? {
+            'J' => Some(Region::Japan),
+            'E' => Some(Region::Usa),
+            'P' | 'X' | 'Y' | 'Z' => Some(Region::Europe),
+            'D' => Some(Region::Germany),
+            'F' => Some(Region::France),
+            'I' => Some(Region::Italy),
+            'S' => Some(Region::Spain),
+            'H' => Some(Region::Netherlands),
+            'U' => Some(Region::Australia),
+            'K' => Some(Region::Korea),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Region::Japan => "Japan",
+            Region::Usa => "USA",
+            Region::Europe => "Europe",
+            Region::Germany => "Germany",
+            Region::France => "France",
+            Region::Italy => "Italy",
+            Region::Spain => "Spain",
+            Region::Netherlands => "Netherlands",
+            Region::Australia => "Australia",
+            Region::Korea => "Korea",
+            Region::Overseas => "Overseas",
+            Region::Unknown => "Unknown",
+        })
+    }
+}
+
+/// Header data a region guess was based on, from most to least specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RegionSource {
+    ManufacturerCode,
+    DestinationCode,
+    Licensee,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegionGuess {
+    pub region: Region,
+    pub source: RegionSource,
+}
+
+// Old licensee codes of publishers that only released outside of Japan
+const OVERSEAS_LICENSEES: &[u8] = &[
+    0x0C, 0x13, 0x19, 0x1F, 0x30, 0x3E, 0x44, 0x47, 0x4A, 0x4D, 0x4F, 0x50, 0x51, 0x52, 0x53, 0x54,
+    0x55, 0x56, 0x57, 0x59, 0x5A, 0x5B, 0x5D, 0x60, 0x61, 0x67, 0x69, 0x6E, 0x6F, 0x70, 0x71, 0x72,
+    0x73, 0x75, 0x78, 0x79, 0x7A, 0x7C, 0xB0, 0xBD, 0xCA, 0xDB, 0xFF,
+];
+
+// New licensee codes of publishers that only released outside of Japan
+const OVERSEAS_NEW_LICENSEES: &[&str] = &[
+    "13", "30", "33", "44", "50", "51", "52", "53", "55", "56", "57", "58", "59", "60", "61", "64",
+    "67", "69", "70", "71", "72", "73", "75", "78", "79", "93",
+];
+
+fn is_overseas_licensee(license_code: u8, new_license_code: &str) -> bool {
+    if license_code == licensee::USE_NEW_LICENSEE_CODE {
+        OVERSEAS_NEW_LICENSEES.contains(&new_license_code)
+    } else {
+        OVERSEAS_LICENSEES.contains(&license_code)
+    }
+}
+
+/// Guesses the release region from, in order, the manufacturer code, the destination code
+/// and the licensee. The licensee is only used when the destination code is invalid, and
+/// only identifies publishers that never released in Japan.
+pub fn guess_region(
+    manufacturer_code: Option<&str>,
+    destination: Destination,
+    license_code: u8,
+    new_license_code: &str,
+) -> RegionGuess {
+    if let Some(region) = manufacturer_code.and_then(Region::from_manufacturer_code) {
+        return RegionGuess {
+            region,
+            source: RegionSource::ManufacturerCode,
+        };
+    }
+
+    match destination {
+        Destination::Japan => RegionGuess {
+            region: Region::Japan,
+            source: RegionSource::DestinationCode,
+        },
+        Destination::Overseas => RegionGuess {
+            region: Region::Overseas,
+            source: RegionSource::DestinationCode,
+        },
+        Destination::Unknown(_) if is_overseas_licensee(license_code, new_license_code) => {
+            RegionGuess {
+                region: Region::Overseas,
+                source: RegionSource::Licensee,
+            }
+        }
+        Destination::Unknown(_) => RegionGuess {
+            region: Region::Unknown,
+            source: RegionSource::None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destination() {
+        assert_eq!(Destination::from_code(0x00), Destination::Japan);
+        assert_eq!(Destination::from_code(0x01).to_string(), "Overseas");
+        assert_eq!(Destination::from_code(0x07).to_string(), "Unknown (0x07)");
+        assert_eq!(Destination::from_code(0x07).code(), 0x07);
+    }
+
+    #[test]
+    fn guess_from_manufacturer_code() {
+        let guess = guess_region(Some("AAXE"), Destination::Japan, 0x33, "01");
+        assert_eq!(guess.region, Region::Usa);
+        assert_eq!(guess.source, RegionSource::ManufacturerCode);
+
+        let guess = guess_region(Some("AAXD"), Destination::Overseas, 0x33, "01");
+        assert_eq!(guess.region, Region::Germany);
+    }
+
+    #[test]
+    fn guess_from_destination() {
+        let guess = guess_region(None, Destination::Japan, 0x01, "");
+        assert_eq!(guess.region, Region::Japan);
+        assert_eq!(guess.source, RegionSource::DestinationCode);
+
+        let guess = guess_region(Some("AAXQ"), Destination::Overseas, 0x01, "");
+        assert_eq!(guess.region, Region::Overseas);
+    }
+
+    #[test]
+    fn guess_from_licensee() {
+        let guess = guess_region(None, Destination::Unknown(0x02), 0x78, "");
+        assert_eq!(guess.region, Region::Overseas);
+        assert_eq!(guess.source, RegionSource::Licensee);
+
+        let guess = guess_region(None, Destination::Unknown(0x02), 0x01, "");
+        assert_eq!(guess.region, Region::Unknown);
+        assert_eq!(guess.source, RegionSource::None);
+    }
+}
@@ -0,0 +1,164 @@
+//! Battery-backed save data.
+//!
+//! Saves hold the cartridge RAM in the layout returned by [`Cartridge::ram`]. For MBC2 that is
+//! 512 bytes, one per half-byte cell.
+//!
+//! Cartridges with a real-time clock append the RTC state to the RAM in the format used by
+//! BGB and VisualBoyAdvance: the running and the latched S, M, H, DL and DH registers as
+//! 32-bit little endian values, followed by the Unix time they were saved at.
+
+use core::convert::TryInto;
+
+use crate::error::SaveError;
+use crate::mappers::rtc::{Rtc, RtcRegisters, RTC_SECONDS};
+use crate::mappers::Cartridge;
+
+/// Length of the RTC state with a 64-bit timestamp.
+pub const RTC_SAVE_LEN: usize = 48;
+/// Length of the RTC state written by older emulators with a 32-bit timestamp.
+pub const RTC_SAVE_LEN_LEGACY: usize = 44;
+
+const RTC_REGISTER_COUNT: usize = 5;
+
+/// Save data of the cartridge, to be written to disk.
+pub fn save_ram<C: Cartridge + ?Sized>(cartridge: &C) -> &[u8] {
+    cartridge.ram()
+}
+
+/// Restores the cartridge RAM from `save`, which must be exactly as long as the RAM.
+///
+/// MBC2 only keeps the lower nibble of each cell, so saves written with the upper nibbles set
+/// or clear load, and save again, the same way.
+pub fn load_ram<C: Cartridge + ?Sized>(cartridge: &mut C, save: &[u8]) -> Result<(), SaveError> {
+    cartridge.load_ram(save)
+}
+
+/// RTC state to append to the save, always with a 64-bit timestamp.
+pub fn save_rtc(rtc: &Rtc) -> [u8; RTC_SAVE_LEN] {
+    let mut save = [0; RTC_SAVE_LEN];
+    let registers = [rtc.registers(), rtc.latched()];
+    for (index, chunk) in save[..40].chunks_exact_mut(4).enumerate() {
+        let registers = registers[index / RTC_REGISTER_COUNT];
+        let value = registers.read(RTC_SECONDS + (index % RTC_REGISTER_COUNT) as u8);
+        chunk.copy_from_slice(&(value.unwrap() as u32).to_le_bytes());
+    }
+    save[40..].copy_from_slice(&rtc.timestamp().to_le_bytes());
+    save
+}
+
+/// Restores the RTC from state saved by [`save_rtc`] or by an emulator using a 32-bit
+/// timestamp. The time since the save is counted the next time the clock is accessed.
+pub fn load_rtc(rtc: &mut Rtc, save: &[u8]) -> Result<(), SaveError> {
+    let timestamp = match save.len() {
+        RTC_SAVE_LEN => u64::from_le_bytes(save[40..48].try_into().unwrap()),
+        RTC_SAVE_LEN_LEGACY => u32::from_le_bytes(save[40..44].try_into().unwrap()) as u64,
+        len => {
+            return Err(SaveError::SizeMismatch {
+                len,
+                expected: RTC_SAVE_LEN,
+            })
+        }
+    };
+
+    let mut registers = [RtcRegisters::default(); 2];
+    for (index, chunk) in save[..40].chunks_exact(4).enumerate() {
+        let value = u32::from_le_bytes(chunk.try_into().unwrap());
+        let register = RTC_SECONDS + (index % RTC_REGISTER_COUNT) as u8;
+        registers[index / RTC_REGISTER_COUNT].write(register, value as u8);
+    }
+    rtc.restore(registers[0], registers[1], timestamp);
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::mappers::rtc::ManualClock;
+    use crate::mappers::{Mbc2, Mbc3, RomOnly};
+
+    #[test]
+    fn load_and_save() {
+        let mut cartridge = RomOnly::new(vec![0; 0x8000], 0x2000);
+        let mut save = vec![0; 0x2000];
+        save[0x10] = 0x42;
+
+        load_ram(&mut cartridge, &save).unwrap();
+        assert_eq!(cartridge.read(0xA010), 0x42);
+        assert_eq!(save_ram(&cartridge), &save[..]);
+    }
+
+    #[test]
+    fn load_wrong_size() {
+        let mut cartridge = RomOnly::new(vec![0; 0x8000], 0x2000);
+        assert_eq!(
+            load_ram(&mut cartridge, &[0; 0x800]),
+            Err(SaveError::SizeMismatch {
+                len: 0x800,
+                expected: 0x2000
+            })
+        );
+    }
+
+    #[test]
+    fn load_mbc2() {
+        let mut cartridge = Mbc2::new(vec![0; 0x8000]);
+        let mut save = vec![0xF0; 512];
+        save[1] = 0xF7;
+        save[2] = 0x07;
+
+        load_ram(&mut cartridge, &save).unwrap();
+        cartridge.write(0x0000, 0x0A);
+        assert_eq!(cartridge.read(0xA001), 0xF7);
+        assert_eq!(cartridge.read(0xA002), 0xF7);
+        assert_eq!(cartridge.read(0xA000), 0xF0);
+
+        // Cells loaded with the upper nibble set save the same as cells the game wrote
+        cartridge.write(0xA003, 0xF7);
+        assert_eq!(save_ram(&cartridge)[1], 0x07);
+        assert_eq!(save_ram(&cartridge)[3], 0x07);
+        assert_eq!(save_ram(&cartridge)[0], 0x00);
+    }
+
+    #[test]
+    fn rtc_round_trip() {
+        let clock = ManualClock::new(1_000_000);
+        let mut cartridge = Mbc3::with_rtc(vec![0; 0x8000], 0x2000, &clock);
+        cartridge.write(0x0000, 0x0A);
+        cartridge.write(0x4000, 0x0A);
+        cartridge.write(0xA000, 0x05);
+
+        let save = save_rtc(cartridge.rtc().unwrap());
+        assert_eq!(save[8], 0x05);
+        assert_eq!(save[28], 0x05);
+        assert_eq!(&save[40..], &1_000_000u64.to_le_bytes());
+
+        // The clock keeps counting while the game is not running
+        clock.advance(120);
+        let mut cartridge = Mbc3::with_rtc(vec![0; 0x8000], 0x2000, &clock);
+        load_rtc(cartridge.rtc_mut().unwrap(), &save).unwrap();
+        cartridge.write(0x0000, 0x0A);
+        cartridge.write(0x6000, 0x00);
+        cartridge.write(0x6000, 0x01);
+        cartridge.write(0x4000, 0x09);
+        assert_eq!(cartridge.read(0xA000), 2);
+        cartridge.write(0x4000, 0x0A);
+        assert_eq!(cartridge.read(0xA000), 5);
+    }
+
+    #[test]
+    fn load_rtc_legacy() {
+        let mut save = [0; RTC_SAVE_LEN_LEGACY];
+        save[0] = 30;
+        save[16] = 0x41;
+        save[40..].copy_from_slice(&500u32.to_le_bytes());
+
+        let mut rtc = Rtc::new(0);
+        load_rtc(&mut rtc, &save).unwrap();
+        assert_eq!(rtc.timestamp(), 500);
+        assert_eq!(rtc.registers().seconds, 30);
+        assert_eq!(rtc.registers().days, 0x100);
+        assert!(rtc.registers().halted);
+
+        assert!(load_rtc(&mut rtc, &save[..40]).is_err());
+    }
+}
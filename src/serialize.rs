@@ -0,0 +1,203 @@
+//! Serialization of the decoded header, enabled by the `serde` feature.
+//!
+//! [`DMG`] serializes both the raw header bytes and their interpretation, including the
+//! result of every validation. The ROM image itself is left out.
+
+use alloc::vec::Vec;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde::Serialize as DeriveSerialize;
+
+use crate::cartridge::Mapper;
+use crate::header::DMG;
+use crate::logo::Model;
+use crate::size::{RamSize, RamSizeMismatch, RomSizeMismatch};
+
+#[derive(DeriveSerialize)]
+struct Logo<'a> {
+    bytes: &'a [u8],
+    valid_dmg: bool,
+    valid_cgb: bool,
+    mismatches: Vec<usize>,
+}
+
+#[derive(DeriveSerialize)]
+struct Cartridge {
+    code: u8,
+    name: &'static str,
+    mapper: Mapper,
+    ram: bool,
+    battery: bool,
+    rtc: bool,
+    rumble: bool,
+    sensor: bool,
+}
+
+#[derive(DeriveSerialize)]
+struct RomSizeInfo {
+    code: u8,
+    bytes: Option<usize>,
+    banks: Option<usize>,
+    mismatch: Option<RomSizeMismatch>,
+}
+
+#[derive(DeriveSerialize)]
+struct RamSizeInfo {
+    code: u8,
+    kind: RamSize,
+    bytes: Option<usize>,
+    banks: Option<usize>,
+    mismatch: Option<RamSizeMismatch>,
+}
+
+#[derive(DeriveSerialize)]
+struct Licensee<'a> {
+    code: u8,
+    new_code: &'a str,
+    publisher: Option<&'static str>,
+}
+
+#[derive(DeriveSerialize)]
+struct Checksum<T> {
+    stored: T,
+    computed: T,
+    valid: bool,
+}
+
+impl<S: AsRef<[u8]>> Serialize for DMG<S> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        let cartridge_type = self.cartridge_type();
+        let rom_size = self.rom_size();
+        let ram_size = self.ram_size();
+        let region = self.region();
+
+        let mut state = serializer.serialize_struct("DMG", 19)?;
+        state.serialize_field("entry_point", &self.get_entry_point())?;
+        state.serialize_field(
+            "logo",
+            &Logo {
+                bytes: self.get_nintendo_logo(),
+                valid_dmg: self.is_logo_valid(Model::Dmg),
+                valid_cgb: self.is_logo_valid(Model::Cgb),
+                mismatches: self.logo_diff().indices().collect(),
+            },
+        )?;
+        state.serialize_field("title", self.get_title())?;
+        state.serialize_field("title_bytes", self.title_bytes())?;
+        state.serialize_field("title_layout", &self.title_layout())?;
+        state.serialize_field("manufacturer_code", &self.manufacturer_code())?;
+        state.serialize_field("cgb_flag", &self.get_cgb_flag())?;
+        state.serialize_field("cgb_support", &self.cgb_support())?;
+        state.serialize_field("sgb_flag", &self.get_sgb_flag())?;
+        state.serialize_field("sgb_status", &self.sgb_status())?;
+        state.serialize_field(
+            "cartridge_type",
+            &Cartridge {
+                code: cartridge_type.code(),
+                name: cartridge_type.name(),
+                mapper: cartridge_type.mapper(),
+                ram: cartridge_type.has_ram(),
+                battery: cartridge_type.has_battery(),
+                rtc: cartridge_type.has_rtc(),
+                rumble: cartridge_type.has_rumble(),
+                sensor: cartridge_type.has_sensor(),
+            },
+        )?;
+        state.serialize_field(
+            "rom_size",
+            &RomSizeInfo {
+                code: rom_size.code(),
+                bytes: rom_size.bytes(),
+                banks: rom_size.banks(),
+                mismatch: self.verify_rom_size().err(),
+            },
+        )?;
+        state.serialize_field(
+            "ram_size",
+            &RamSizeInfo {
+                code: self.header_view().get_ram_size(),
+                kind: ram_size,
+                bytes: ram_size.bytes(),
+                banks: ram_size.banks(),
+                mismatch: self.verify_ram_size().err(),
+            },
+        )?;
+        state.serialize_field("destination", &self.destination())?;
+        state.serialize_field("region", &region)?;
+        state.serialize_field(
+            "licensee",
+            &Licensee {
+                code: self.get_license_code(),
+                new_code: self.get_new_license_code(),
+                publisher: self.publisher(),
+            },
+        )?;
+        state.serialize_field("version", &self.get_mask_romversion_number())?;
+        state.serialize_field(
+            "header_checksum",
+            &Checksum {
+                stored: self.get_header_checksum(),
+                computed: self.computed_header_checksum(),
+                valid: self.verify_header_checksum().is_ok(),
+            },
+        )?;
+        state.serialize_field(
+            "global_checksum",
+            &Checksum {
+                stored: self.get_global_checksum(),
+                computed: self.computed_global_checksum(),
+                valid: self.verify_global_checksum().is_ok(),
+            },
+        )?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::fs;
+
+    fn serialize_fixture() -> Value {
+        let header = DMG::new(fs::read("test_roms/header_only_test.gb").unwrap()).unwrap();
+        serde_json::to_value(&header).unwrap()
+    }
+
+    #[test]
+    fn raw_and_decoded_values() {
+        let value = serialize_fixture();
+        assert_eq!(value["title"], "GBLOADERTEST1234");
+        assert_eq!(value["title_layout"], "Legacy");
+        assert_eq!(value["cgb_support"], "DmgOnly");
+        assert_eq!(value["sgb_status"], "Supported");
+        assert_eq!(value["cartridge_type"]["code"], 1);
+        assert_eq!(value["cartridge_type"]["name"], "MBC1");
+        assert_eq!(value["cartridge_type"]["mapper"], "Mbc1");
+        assert_eq!(value["rom_size"]["bytes"], 0x20000);
+        assert_eq!(value["ram_size"]["kind"], "Kib32");
+        assert_eq!(value["destination"], "Overseas");
+        assert_eq!(
+            value["region"],
+            json!({"region": "Overseas", "source": "DestinationCode"})
+        );
+        assert_eq!(value["licensee"]["publisher"], "Nintendo");
+    }
+
+    #[test]
+    fn validation_results() {
+        let value = serialize_fixture();
+        assert_eq!(value["logo"]["valid_dmg"], true);
+        assert_eq!(value["logo"]["mismatches"], json!([]));
+        assert_eq!(
+            value["header_checksum"],
+            json!({"stored": 0xFF, "computed": 0xFF, "valid": true})
+        );
+        assert_eq!(value["global_checksum"]["valid"], false);
+        assert_eq!(
+            value["rom_size"]["mismatch"],
+            json!({"Underdump": {"declared": 0x20000, "actual": 0x150}})
+        );
+        assert!(value.get("rom_data").is_none());
+    }
+}
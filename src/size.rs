@@ -0,0 +1,417 @@
+//! ROM and RAM sizes declared at 0x0148 and 0x0149.
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::cartridge::{CartridgeType, Mapper};
+
+pub const ROM_BANK_SIZE: usize = 0x4000;
+pub const RAM_BANK_SIZE: usize = 0x2000;
+pub const MBC2_RAM_CELLS: usize = 512;
+pub const MBC7_EEPROM_SIZE: usize = 256;
+
+/// ROM size code read from 0x0148.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RomSize {
+    code: u8,
+}
+
+impl RomSize {
+    pub fn from_code(code: u8) -> RomSize {
+        RomSize { code }
+    }
+
+    /// Smallest official or unofficial code whose size is exactly `len` bytes.
+    pub fn for_len(len: usize) -> Option<RomSize> {
+        (0x00..=0x08)
+            .chain(0x52..=0x54)
+            .map(RomSize::from_code)
+            .find(|size| size.bytes() == Some(len))
+    }
+
+    pub fn code(self) -> u8 {
+        self.code
+    }
+
+    /// Number of 16 KiB banks. Codes 0x52 - 0x54 are unofficial and only appear in a few
+    /// Pan Docs listings, but are supported for completeness.
+    pub fn banks(self) -> Option<usize> {
+        match self.code {
+            0x00..=0x08 => Some(2 << self.code),
+            0x52 => Some(72),
+            0x53 => Some(80),
+            0x54 => Some(96),
+            _ => None,
+        }
+    }
+
+    /// Size in bytes, calculated as 32 KiB << N for the official codes.
+    pub fn bytes(self) -> Option<usize> {
+        self.banks().map(|banks| banks * ROM_BANK_SIZE)
+    }
+}
+
+impl fmt::Display for RomSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.bytes() {
+            Some(bytes) => write!(f, "{} KiB ({} banks)", bytes / 1024, self.banks().unwrap()),
+            None => write!(f, "unknown (0x{:02X})", self.code),
+        }
+    }
+}
+
+/// Disagreement between the declared ROM size and the length of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RomSizeMismatch {
+    // The image is longer than the header declares, e.g. a dump read past the end of the chip
+    Overdump { declared: usize, actual: usize },
+    // The image is shorter than the header declares, e.g. a truncated dump
+    Underdump { declared: usize, actual: usize },
+    // The size code at 0x0148 is not a known value
+    UnknownCode(u8),
+}
+
+impl fmt::Display for RomSizeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RomSizeMismatch::Overdump { declared, actual } => write!(
+                f,
+                "image is {} bytes but the header declares {} bytes (overdump)",
+                actual, declared
+            ),
+            RomSizeMismatch::Underdump { declared, actual } => write!(
+                f,
+                "image is {} bytes but the header declares {} bytes (underdump)",
+                actual, declared
+            ),
+            RomSizeMismatch::UnknownCode(code) => write!(f, "unknown ROM size code 0x{:02X}", code),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RomSizeMismatch {}
+
+/// Compares the declared ROM size against the length of the image.
+pub fn verify_rom_size(size: RomSize, len: usize) -> Result<(), RomSizeMismatch> {
+    let declared = size
+        .bytes()
+        .ok_or(RomSizeMismatch::UnknownCode(size.code()))?;
+
+    if len > declared {
+        Err(RomSizeMismatch::Overdump {
+            declared,
+            actual: len,
+        })
+    } else if len < declared {
+        Err(RomSizeMismatch::Underdump {
+            declared,
+            actual: len,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// External RAM of the cartridge, decoded from 0x0149 and the cartridge type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RamSize {
+    None,       // 0x00
+    Kib2,       // 0x01, unofficial and never used by a licensed game
+    Kib8,       // 0x02
+    Kib32,      // 0x03, 4 banks of 8 KiB
+    Kib128,     // 0x04, 16 banks of 8 KiB
+    Kib64,      // 0x05, 8 banks of 8 KiB
+    Mbc2,       // 512 x 4 bits built into the MBC2, 0x0149 is 0x00
+    Mbc7Eeprom, // 256 byte serial EEPROM on MBC7 boards, not mapped as RAM banks
+    Unknown(u8),
+}
+
+impl RamSize {
+    /// Decodes the code at 0x0149 on its own, without cartridge type special cases.
+    pub fn from_code(code: u8) -> RamSize {
+        match code {
+            0x00 => RamSize::None,
+            0x01 => RamSize::Kib2,
+            0x02 => RamSize::Kib8,
+            0x03 => RamSize::Kib32,
+            0x04 => RamSize::Kib128,
+            0x05 => RamSize::Kib64,
+            code => RamSize::Unknown(code),
+        }
+    }
+
+    /// Decodes the code at 0x0149, replacing it with the built-in memory of MBC2 and MBC7
+    /// cartridges, whose headers declare no external RAM.
+    pub fn new(cartridge_type: CartridgeType, code: u8) -> RamSize {
+        match cartridge_type.mapper() {
+            Mapper::Mbc2 => RamSize::Mbc2,
+            Mapper::Mbc7 => RamSize::Mbc7Eeprom,
+            _ => RamSize::from_code(code),
+        }
+    }
+
+    /// Code declaring this size at 0x0149. MBC2 and MBC7 memory is built in, so their
+    /// headers declare 0x00.
+    pub fn code(self) -> u8 {
+        match self {
+            RamSize::None | RamSize::Mbc2 | RamSize::Mbc7Eeprom => 0x00,
+            RamSize::Kib2 => 0x01,
+            RamSize::Kib8 => 0x02,
+            RamSize::Kib32 => 0x03,
+            RamSize::Kib128 => 0x04,
+            RamSize::Kib64 => 0x05,
+            RamSize::Unknown(code) => code,
+        }
+    }
+
+    /// Size in bytes. MBC2 RAM is reported as one byte per 4-bit cell, which is also how
+    /// emulators usually store it in save files.
+    pub fn bytes(self) -> Option<usize> {
+        match self {
+            RamSize::None => Some(0),
+            RamSize::Kib2 => Some(2 * 1024),
+            RamSize::Kib8 => Some(8 * 1024),
+            RamSize::Kib32 => Some(32 * 1024),
+            RamSize::Kib128 => Some(128 * 1024),
+            RamSize::Kib64 => Some(64 * 1024),
+            RamSize::Mbc2 => Some(MBC2_RAM_CELLS),
+            RamSize::Mbc7Eeprom => Some(MBC7_EEPROM_SIZE),
+            RamSize::Unknown(_) => None,
+        }
+    }
+
+    /// Number of 8 KiB banks switchable at 0xA000 - 0xBFFF. A 2 KiB or MBC2 RAM still
+    /// occupies a single (mirrored) bank, the MBC7 EEPROM none.
+    pub fn banks(self) -> Option<usize> {
+        match self {
+            RamSize::None | RamSize::Mbc7Eeprom => Some(0),
+            RamSize::Kib2 | RamSize::Kib8 | RamSize::Mbc2 => Some(1),
+            RamSize::Kib32 => Some(4),
+            RamSize::Kib128 => Some(16),
+            RamSize::Kib64 => Some(8),
+            RamSize::Unknown(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for RamSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RamSize::None => f.write_str("none"),
+            RamSize::Mbc2 => f.write_str("512 x 4 bits (MBC2)"),
+            RamSize::Mbc7Eeprom => f.write_str("256 bytes (MBC7 EEPROM)"),
+            RamSize::Unknown(code) => write!(f, "unknown (0x{:02X})", code),
+            size => {
+                let banks = size.banks().unwrap();
+                write!(
+                    f,
+                    "{} KiB ({} {})",
+                    size.bytes().unwrap() / 1024,
+                    banks,
+                    if banks == 1 { "bank" } else { "banks" }
+                )
+            }
+        }
+    }
+}
+
+/// Disagreement between the RAM size code and the cartridge type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RamSizeMismatch {
+    // RAM is declared on a cartridge without external RAM, including MBC2 and MBC7
+    DeclaredWithoutRam {
+        cartridge_type: CartridgeType,
+        code: u8,
+    },
+    // The cartridge type has external RAM but none is declared
+    MissingRam {
+        cartridge_type: CartridgeType,
+    },
+    // The size code at 0x0149 is not a known value
+    UnknownCode(u8),
+}
+
+impl fmt::Display for RamSizeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RamSizeMismatch::DeclaredWithoutRam {
+                cartridge_type,
+                code,
+            } => write!(
+                f,
+                "RAM size code 0x{:02X} declared on {}, which has no external RAM",
+                code, cartridge_type
+            ),
+            RamSizeMismatch::MissingRam { cartridge_type } => {
+                write!(
+                    f,
+                    "{} has external RAM but none is declared",
+                    cartridge_type
+                )
+            }
+            RamSizeMismatch::UnknownCode(code) => write!(f, "unknown RAM size code 0x{:02X}", code),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RamSizeMismatch {}
+
+// Cartridge types whose RAM, if any, is sized by 0x0149
+fn has_external_ram(cartridge_type: CartridgeType) -> bool {
+    cartridge_type.has_ram()
+        && !matches!(
+            cartridge_type.mapper(),
+            Mapper::Mbc2 | Mapper::Mbc7 | Mapper::Unknown
+        )
+}
+
+/// Checks the RAM size code at 0x0149 against the cartridge type.
+pub fn verify_ram_size(cartridge_type: CartridgeType, code: u8) -> Result<(), RamSizeMismatch> {
+    if let RamSize::Unknown(code) = RamSize::from_code(code) {
+        return Err(RamSizeMismatch::UnknownCode(code));
+    }
+
+    let external_ram = has_external_ram(cartridge_type);
+    if code != 0x00 && !external_ram && cartridge_type.mapper() != Mapper::Unknown {
+        Err(RamSizeMismatch::DeclaredWithoutRam {
+            cartridge_type,
+            code,
+        })
+    } else if code == 0x00
+        && external_ram
+        && matches!(
+            cartridge_type.mapper(),
+            Mapper::None | Mapper::Mbc1 | Mapper::Mbc3 | Mapper::Mbc5 | Mapper::Mmm01
+        )
+    {
+        Err(RamSizeMismatch::MissingRam { cartridge_type })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rom_size_official_codes() {
+        assert_eq!(RomSize::from_code(0x00).bytes(), Some(32 * 1024));
+        assert_eq!(RomSize::from_code(0x00).banks(), Some(2));
+        assert_eq!(RomSize::from_code(0x05).bytes(), Some(1024 * 1024));
+        assert_eq!(RomSize::from_code(0x08).banks(), Some(512));
+    }
+
+    #[test]
+    fn rom_size_unofficial_codes() {
+        assert_eq!(RomSize::from_code(0x52).banks(), Some(72));
+        assert_eq!(RomSize::from_code(0x53).banks(), Some(80));
+        assert_eq!(RomSize::from_code(0x54).bytes(), Some(96 * 0x4000));
+        assert_eq!(RomSize::from_code(0x09).bytes(), None);
+    }
+
+    #[test]
+    fn rom_size_for_len() {
+        assert_eq!(RomSize::for_len(0x8000), Some(RomSize::from_code(0x00)));
+        assert_eq!(
+            RomSize::for_len(80 * 0x4000),
+            Some(RomSize::from_code(0x53))
+        );
+        assert_eq!(RomSize::for_len(0x8001), None);
+    }
+
+    #[test]
+    fn rom_size_display() {
+        assert_eq!(RomSize::from_code(0x02).to_string(), "128 KiB (8 banks)");
+        assert_eq!(RomSize::from_code(0xAA).to_string(), "unknown (0xAA)");
+    }
+
+    #[test]
+    fn ram_size_codes() {
+        assert_eq!(RamSize::from_code(0x03).bytes(), Some(32 * 1024));
+        assert_eq!(RamSize::from_code(0x03).banks(), Some(4));
+        assert_eq!(RamSize::from_code(0x04).banks(), Some(16));
+        assert_eq!(RamSize::from_code(0x05).banks(), Some(8));
+        assert_eq!(RamSize::from_code(0x06), RamSize::Unknown(0x06));
+        assert!((0x00..=0x06).all(|code| RamSize::from_code(code).code() == code));
+        assert_eq!(RamSize::from_code(0x02).to_string(), "8 KiB (1 bank)");
+        assert_eq!(RamSize::from_code(0x03).to_string(), "32 KiB (4 banks)");
+    }
+
+    #[test]
+    fn ram_size_special_cases() {
+        let mbc2 = RamSize::new(CartridgeType::Mbc2Battery, 0x00);
+        assert_eq!(mbc2, RamSize::Mbc2);
+        assert_eq!(mbc2.bytes(), Some(512));
+        assert_eq!(mbc2.banks(), Some(1));
+
+        let mbc7 = RamSize::new(CartridgeType::Mbc7SensorRumbleRamBattery, 0x00);
+        assert_eq!(mbc7, RamSize::Mbc7Eeprom);
+        assert_eq!(mbc7.banks(), Some(0));
+        assert_eq!(mbc7.code(), 0x00);
+
+        assert_eq!(RamSize::new(CartridgeType::Mbc1Ram, 0x03), RamSize::Kib32);
+    }
+
+    #[test]
+    fn verify_ram_size_combinations() {
+        assert_eq!(verify_ram_size(CartridgeType::RomOnly, 0x00), Ok(()));
+        assert_eq!(verify_ram_size(CartridgeType::Mbc3RamBattery, 0x03), Ok(()));
+        assert_eq!(verify_ram_size(CartridgeType::Mbc2Battery, 0x00), Ok(()));
+        assert_eq!(
+            verify_ram_size(CartridgeType::Mbc1, 0x03),
+            Err(RamSizeMismatch::DeclaredWithoutRam {
+                cartridge_type: CartridgeType::Mbc1,
+                code: 0x03
+            })
+        );
+        assert_eq!(
+            verify_ram_size(CartridgeType::Mbc2Battery, 0x02),
+            Err(RamSizeMismatch::DeclaredWithoutRam {
+                cartridge_type: CartridgeType::Mbc2Battery,
+                code: 0x02
+            })
+        );
+        assert_eq!(
+            verify_ram_size(CartridgeType::Mbc5RamBattery, 0x00),
+            Err(RamSizeMismatch::MissingRam {
+                cartridge_type: CartridgeType::Mbc5RamBattery
+            })
+        );
+        assert_eq!(
+            verify_ram_size(CartridgeType::Mbc1Ram, 0x09),
+            Err(RamSizeMismatch::UnknownCode(0x09))
+        );
+    }
+
+    #[test]
+    fn verify_rom_size_mismatch() {
+        let size = RomSize::from_code(0x01);
+        assert_eq!(verify_rom_size(size, 0x10000), Ok(()));
+        assert_eq!(
+            verify_rom_size(size, 0x20000),
+            Err(RomSizeMismatch::Overdump {
+                declared: 0x10000,
+                actual: 0x20000
+            })
+        );
+        assert_eq!(
+            verify_rom_size(size, 0x8000),
+            Err(RomSizeMismatch::Underdump {
+                declared: 0x10000,
+                actual: 0x8000
+            })
+        );
+        assert_eq!(
+            verify_rom_size(RomSize::from_code(0x20), 0x8000),
+            Err(RomSizeMismatch::UnknownCode(0x20))
+        );
+    }
+}
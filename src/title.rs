@@ -0,0 +1,117 @@
+//! Decoding of the title bytes, which are not guaranteed to be ASCII.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[cfg(feature = "alloc")]
+use crate::error::LoadError;
+
+/// How title bytes outside of printable ASCII are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TitlePolicy {
+    // Fail to load with LoadError::InvalidTitle on any byte outside 0x20 - 0x7E
+    StrictAscii,
+    // Replace bytes outside 0x20 - 0x7E with U+FFFD
+    #[default]
+    Lossy,
+    // Map every byte to the code point of the same value (ISO 8859-1), so no byte is lost
+    Raw,
+    // Decode as JIS X 0201, the single byte subset of Shift-JIS: 0xA1 - 0xDF become half-width katakana
+    JisX0201,
+}
+
+#[cfg(feature = "alloc")]
+fn is_printable_ascii(byte: u8) -> bool {
+    (0x20..=0x7E).contains(&byte)
+}
+
+#[cfg(feature = "alloc")]
+fn decode_jis_x0201(byte: u8) -> char {
+    match byte {
+        0x5C => '\u{A5}',   // YEN SIGN
+        0x7E => '\u{203E}', // OVERLINE
+        0x20..=0x7D => byte as char,
+        0xA1..=0xDF => core::char::from_u32(0xFF61 + (byte - 0xA1) as u32).unwrap(),
+        _ => core::char::REPLACEMENT_CHARACTER,
+    }
+}
+
+/// Decodes title bytes located at ROM address `start` according to `policy`.
+#[cfg(feature = "alloc")]
+pub fn decode_title(bytes: &[u8], start: usize, policy: TitlePolicy) -> Result<String, LoadError> {
+    match policy {
+        TitlePolicy::StrictAscii => {
+            match bytes.iter().position(|&byte| !is_printable_ascii(byte)) {
+                Some(index) => Err(LoadError::InvalidTitle {
+                    offset: start + index,
+                    byte: bytes[index],
+                }),
+                None => Ok(bytes.iter().map(|&byte| byte as char).collect()),
+            }
+        }
+        TitlePolicy::Lossy => Ok(bytes
+            .iter()
+            .map(|&byte| {
+                if is_printable_ascii(byte) {
+                    byte as char
+                } else {
+                    core::char::REPLACEMENT_CHARACTER
+                }
+            })
+            .collect()),
+        TitlePolicy::Raw => Ok(bytes.iter().map(|&byte| byte as char).collect()),
+        TitlePolicy::JisX0201 => Ok(bytes.iter().map(|&byte| decode_jis_x0201(byte)).collect()),
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_ascii() {
+        assert_eq!(
+            decode_title(b"TETRIS", 0x134, TitlePolicy::StrictAscii),
+            Ok("TETRIS".to_string())
+        );
+        assert_eq!(
+            decode_title(b"TET\xFFRIS", 0x134, TitlePolicy::StrictAscii),
+            Err(LoadError::InvalidTitle {
+                offset: 0x137,
+                byte: 0xFF
+            })
+        );
+    }
+
+    #[test]
+    fn lossy() {
+        assert_eq!(
+            decode_title(b"TET\xFFRIS\x01", 0x134, TitlePolicy::Lossy),
+            Ok("TET\u{FFFD}RIS\u{FFFD}".to_string())
+        );
+    }
+
+    #[test]
+    fn raw() {
+        let title = decode_title(b"A\xE9\xFF", 0x134, TitlePolicy::Raw).unwrap();
+        assert_eq!(title, "A\u{E9}\u{FF}");
+        assert_eq!(
+            title.chars().map(|c| c as u8).collect::<Vec<_>>(),
+            b"A\xE9\xFF"
+        );
+    }
+
+    #[test]
+    fn jis_x0201() {
+        // "ポケモン" in half-width katakana: ﾎﾟｹﾓﾝ
+        assert_eq!(
+            decode_title(b"\xCE\xDF\xB9\xD3\xDD", 0x134, TitlePolicy::JisX0201),
+            Ok("\u{FF8E}\u{FF9F}\u{FF79}\u{FF93}\u{FF9D}".to_string())
+        );
+        assert_eq!(
+            decode_title(b"\x5C\x7E\x80", 0x134, TitlePolicy::JisX0201),
+            Ok("\u{A5}\u{203E}\u{FFFD}".to_string())
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Zero-copy access to the header of a borrowed ROM image.
+
+use core::convert::TryInto;
+
+use crate::cartridge::CartridgeType;
+use crate::checksum::{self, ChecksumMismatch};
+use crate::error::LoadError;
+use crate::header::{
+    CgbSupport, SgbStatus, TitleLayout, CGB_FLAG_ADDRESS, HEADER_END, MANUFACTURER_CODE_START,
+    TITLE_START,
+};
+use crate::licensee;
+use crate::logo::{self, LogoDiff, Model};
+use crate::region::{self, Destination, RegionGuess};
+use crate::size::{self, RamSize, RamSizeMismatch, RomSize};
+
+pub const HEADER_START: usize = 0x100;
+pub const HEADER_LEN: usize = HEADER_END - HEADER_START;
+
+/// Borrowed header at 0x0100 - 0x014F, decoding fields on access without allocating.
+///
+/// Unlike `DMG` nothing is validated up front, so a view over a header
+/// with an invalid new licensee code or title can still be inspected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HeaderView<'a> {
+    header: &'a [u8; HEADER_LEN],
+}
+
+/// Owned copy of the 0x50 header bytes, e.g. read on its own from the start of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OwnedHeader {
+    header: [u8; HEADER_LEN],
+}
+
+impl OwnedHeader {
+    pub fn new(header: [u8; HEADER_LEN]) -> OwnedHeader {
+        OwnedHeader { header }
+    }
+
+    pub fn view(&self) -> HeaderView<'_> {
+        HeaderView::from_header(&self.header)
+    }
+}
+
+impl<'a> HeaderView<'a> {
+    /// Borrows the header of a ROM image, which must be at least 0x150 bytes long.
+    pub fn new(rom_data: &'a [u8]) -> Result<HeaderView<'a>, LoadError> {
+        let header = rom_data
+            .get(HEADER_START..HEADER_END)
+            .ok_or(LoadError::TooShort {
+                len: rom_data.len(),
+                required: HEADER_END,
+            })?;
+
+        // The range above is exactly HEADER_LEN bytes long
+        Ok(HeaderView {
+            header: header.try_into().unwrap(),
+        })
+    }
+
+    /// Borrows the 0x50 header bytes on their own, e.g. read from 0x0100 of a file.
+    pub fn from_header(header: &'a [u8; HEADER_LEN]) -> HeaderView<'a> {
+        HeaderView { header }
+    }
+
+    /// The raw header bytes, starting at 0x0100.
+    pub fn as_bytes(&self) -> &'a [u8; HEADER_LEN] {
+        self.header
+    }
+
+    fn byte(&self, address: usize) -> u8 {
+        self.header[address - HEADER_START]
+    }
+
+    fn bytes(&self, start: usize, end: usize) -> &'a [u8] {
+        &self.header[start - HEADER_START..end - HEADER_START]
+    }
+
+    /// The 4 bytes of code at the entry point, usually a NOP and a jump.
+    pub fn entry_code(&self) -> &'a [u8] {
+        self.bytes(HEADER_START, logo::LOGO_START)
+    }
+
+    pub fn get_nintendo_logo(&self) -> &'a [u8; logo::LOGO_LEN] {
+        self.bytes(logo::LOGO_START, logo::LOGO_END)
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn logo_diff(&self) -> LogoDiff {
+        LogoDiff::new(self.get_nintendo_logo())
+    }
+
+    pub fn is_logo_valid(&self, model: Model) -> bool {
+        self.logo_diff().is_bootable(model)
+    }
+
+    pub fn title_layout(&self) -> TitleLayout {
+        TitleLayout::from_fields(
+            self.get_cgb_flag(),
+            self.bytes(MANUFACTURER_CODE_START, CGB_FLAG_ADDRESS),
+        )
+    }
+
+    /// The undecoded title bytes, without NUL padding.
+    pub fn title_bytes(&self) -> &'a [u8] {
+        let title = self.bytes(TITLE_START, self.title_layout().title_end());
+        let len = title
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(title.len());
+        &title[..len]
+    }
+
+    /// The title, if it is printable ASCII. Use `title::decode_title` for others.
+    pub fn title(&self) -> Option<&'a str> {
+        let title = self.title_bytes();
+        if title.iter().all(|byte| (0x20..=0x7E).contains(byte)) {
+            core::str::from_utf8(title).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn manufacturer_code(&self) -> Option<&'a str> {
+        if self.title_layout() == TitleLayout::CgbWithManufacturerCode {
+            // Detection only accepts ASCII letters and digits
+            core::str::from_utf8(self.bytes(MANUFACTURER_CODE_START, CGB_FLAG_ADDRESS)).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_cgb_flag(&self) -> u8 {
+        self.byte(CGB_FLAG_ADDRESS)
+    }
+
+    pub fn cgb_support(&self) -> CgbSupport {
+        CgbSupport::from_flag(self.get_cgb_flag())
+    }
+
+    /// The new licensee code, if the old licensee code is 0x33 and it is valid ASCII.
+    pub fn new_license_code(&self) -> Option<&'a str> {
+        if self.get_license_code() != licensee::USE_NEW_LICENSEE_CODE {
+            return None;
+        }
+        let code = self.bytes(0x144, 0x146);
+        if code.is_ascii() {
+            core::str::from_utf8(code).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_sgb_flag(&self) -> u8 {
+        self.byte(0x146)
+    }
+
+    pub fn supports_sgb(&self) -> bool {
+        self.sgb_status().is_supported()
+    }
+
+    pub fn sgb_status(&self) -> SgbStatus {
+        SgbStatus::new(self.get_sgb_flag(), self.get_license_code())
+    }
+
+    pub fn get_cartridge_type(&self) -> u8 {
+        self.byte(0x147)
+    }
+
+    pub fn cartridge_type(&self) -> CartridgeType {
+        CartridgeType::from_code(self.get_cartridge_type())
+    }
+
+    pub fn get_rom_size(&self) -> u8 {
+        self.byte(0x148)
+    }
+
+    pub fn rom_size(&self) -> RomSize {
+        RomSize::from_code(self.get_rom_size())
+    }
+
+    /// The raw RAM size code, including a non-zero value on MBC2 cartridges.
+    pub fn get_ram_size(&self) -> u8 {
+        self.byte(0x149)
+    }
+
+    pub fn ram_size(&self) -> RamSize {
+        RamSize::new(self.cartridge_type(), self.get_ram_size())
+    }
+
+    pub fn verify_ram_size(&self) -> Result<(), RamSizeMismatch> {
+        size::verify_ram_size(self.cartridge_type(), self.get_ram_size())
+    }
+
+    pub fn get_destination_code(&self) -> u8 {
+        self.byte(0x14A)
+    }
+
+    pub fn destination(&self) -> Destination {
+        Destination::from_code(self.get_destination_code())
+    }
+
+    pub fn region(&self) -> RegionGuess {
+        region::guess_region(
+            self.manufacturer_code(),
+            self.destination(),
+            self.get_license_code(),
+            self.new_license_code().unwrap_or(""),
+        )
+    }
+
+    pub fn get_license_code(&self) -> u8 {
+        self.byte(0x14B)
+    }
+
+    pub fn publisher(&self) -> Option<&'static str> {
+        licensee::publisher(
+            self.get_license_code(),
+            self.new_license_code().unwrap_or(""),
+        )
+    }
+
+    pub fn get_mask_romversion_number(&self) -> u8 {
+        self.byte(0x14C)
+    }
+
+    pub fn get_header_checksum(&self) -> u8 {
+        self.byte(checksum::HEADER_CHECKSUM_ADDRESS)
+    }
+
+    pub fn computed_header_checksum(&self) -> u8 {
+        checksum::sum_header(self.bytes(
+            checksum::HEADER_CHECKSUM_START,
+            checksum::HEADER_CHECKSUM_END,
+        ))
+    }
+
+    pub fn verify_header_checksum(&self) -> Result<(), ChecksumMismatch<u8>> {
+        let computed = self.computed_header_checksum();
+        if self.get_header_checksum() == computed {
+            Ok(())
+        } else {
+            Err(ChecksumMismatch {
+                stored: self.get_header_checksum(),
+                computed,
+            })
+        }
+    }
+
+    /// The stored global checksum. Computing it needs the whole image, see
+    /// [`checksum::global_checksum`].
+    pub fn get_global_checksum(&self) -> u16 {
+        u16::from_be_bytes([
+            self.byte(checksum::GLOBAL_CHECKSUM_ADDRESS),
+            self.byte(checksum::GLOBAL_CHECKSUM_ADDRESS + 1),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn read_rom() -> Vec<u8> {
+        fs::read("test_roms/header_only_test.gb").unwrap()
+    }
+
+    #[test]
+    fn new_too_short() {
+        assert_eq!(
+            HeaderView::new(&[0; 0x14F]),
+            Err(LoadError::TooShort {
+                len: 0x14F,
+                required: 0x150
+            })
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let rom_data = read_rom();
+        let view = HeaderView::new(&rom_data).unwrap();
+
+        assert_eq!(view.entry_code(), &[0x00, 0xC3, 0x50, 0x01]);
+        assert!(view.is_logo_valid(Model::Dmg));
+        assert_eq!(view.title(), Some("GBLOADERTEST1234"));
+        assert_eq!(view.manufacturer_code(), None);
+        assert_eq!(view.new_license_code(), Some("01"));
+        assert_eq!(view.publisher(), Some("Nintendo"));
+        assert!(view.supports_sgb());
+        assert_eq!(view.cartridge_type(), CartridgeType::Mbc1);
+        assert_eq!(view.rom_size().banks(), Some(8));
+        assert_eq!(view.ram_size(), RamSize::Kib32);
+        assert_eq!(view.destination(), Destination::Overseas);
+        assert_eq!(view.get_mask_romversion_number(), 0);
+        assert!(view.verify_header_checksum().is_ok());
+        assert_eq!(view.get_global_checksum(), 0);
+    }
+
+    #[test]
+    fn from_header() {
+        let rom_data = read_rom();
+        let header: &[u8; HEADER_LEN] = rom_data[0x100..0x150].try_into().unwrap();
+
+        let view = HeaderView::from_header(header);
+        assert_eq!(view, HeaderView::new(&rom_data).unwrap());
+        assert_eq!(view.as_bytes(), header);
+    }
+
+    #[test]
+    fn undecodable_fields() {
+        let mut rom_data = read_rom();
+        rom_data[0x134..0x144].copy_from_slice(b"POKEMON_SLVAAXE\x80");
+        rom_data[0x145] = 0xFF;
+        rom_data[0x136] = 0xFF;
+
+        let view = HeaderView::new(&rom_data).unwrap();
+        assert_eq!(view.title(), None);
+        assert_eq!(view.title_bytes(), b"PO\xFFEMON_SLV");
+        assert_eq!(view.manufacturer_code(), Some("AAXE"));
+        assert_eq!(view.new_license_code(), None);
+    }
+}